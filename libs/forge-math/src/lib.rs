@@ -0,0 +1,299 @@
+//! Shared fixed-point math for the Forge programs.
+//!
+//! Every rate, index and exchange rate is a [`Decimal`] scaled by [`SCALE`] (1e9).
+//! All arithmetic is checked and every division takes an explicit [`Rounding`]
+//! so callers decide which side of the protocol absorbs the dust.
+
+/// Fixed-point scale shared by all programs (1.0 = 1e9)
+pub const SCALE: u128 = 1_000_000_000;
+/// Basis points in 1.0
+pub const BPS_SCALE: u128 = 10_000;
+/// Seconds in a year, for pro-rating annual rates
+pub const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MathError {
+    Overflow,
+    DivideByZero,
+}
+
+#[cfg(feature = "anchor")]
+impl From<MathError> for anchor_lang::error::Error {
+    fn from(_: MathError) -> Self {
+        anchor_lang::prelude::ProgramError::ArithmeticOverflow.into()
+    }
+}
+
+pub type MathResult<T> = core::result::Result<T, MathError>;
+
+/// Direction applied to the remainder of a division
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    Down,
+    Up,
+}
+
+/// `a * b / denominator` with a u128 intermediate and explicit rounding
+pub fn mul_div(a: u128, b: u128, denominator: u128, rounding: Rounding) -> MathResult<u128> {
+    if denominator == 0 {
+        return Err(MathError::DivideByZero);
+    }
+    let product = a.checked_mul(b).ok_or(MathError::Overflow)?;
+    Ok(match rounding {
+        Rounding::Down => product / denominator,
+        Rounding::Up => product.div_ceil(denominator),
+    })
+}
+
+/// `bps` basis points of `amount`
+pub fn bps_of(amount: u128, bps: u64, rounding: Rounding) -> MathResult<u128> {
+    mul_div(amount, bps as u128, BPS_SCALE, rounding)
+}
+
+/// Narrow a u128 result to u64
+pub fn to_u64(value: u128) -> MathResult<u64> {
+    u64::try_from(value).map_err(|_| MathError::Overflow)
+}
+
+/// Unsigned fixed-point number scaled by [`SCALE`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Decimal(u128);
+
+impl Decimal {
+    pub const ZERO: Decimal = Decimal(0);
+    pub const ONE: Decimal = Decimal(SCALE);
+
+    /// Wrap a value that is already scaled by [`SCALE`]
+    pub const fn from_scaled(raw: u128) -> Self {
+        Decimal(raw)
+    }
+
+    /// Raw value scaled by [`SCALE`], as stored in accounts
+    pub const fn to_scaled(self) -> u128 {
+        self.0
+    }
+
+    /// Exact: SCALE is a multiple of BPS_SCALE
+    pub fn from_bps(bps: u64) -> Self {
+        Decimal(bps as u128 * (SCALE / BPS_SCALE))
+    }
+
+    pub fn to_bps(self, rounding: Rounding) -> MathResult<u64> {
+        to_u64(mul_div(self.0, BPS_SCALE, SCALE, rounding)?)
+    }
+
+    /// `numerator / denominator` as a decimal
+    pub fn from_ratio(numerator: u128, denominator: u128, rounding: Rounding) -> MathResult<Self> {
+        Ok(Decimal(mul_div(numerator, SCALE, denominator, rounding)?))
+    }
+
+    pub fn checked_add(self, other: Decimal) -> MathResult<Self> {
+        self.0.checked_add(other.0).map(Decimal).ok_or(MathError::Overflow)
+    }
+
+    pub fn checked_sub(self, other: Decimal) -> MathResult<Self> {
+        self.0.checked_sub(other.0).map(Decimal).ok_or(MathError::Overflow)
+    }
+
+    pub fn checked_mul(self, other: Decimal, rounding: Rounding) -> MathResult<Self> {
+        Ok(Decimal(mul_div(self.0, other.0, SCALE, rounding)?))
+    }
+
+    pub fn checked_div(self, other: Decimal, rounding: Rounding) -> MathResult<Self> {
+        Ok(Decimal(mul_div(self.0, SCALE, other.0, rounding)?))
+    }
+
+    /// `self^exponent` by squaring, each product rounded down
+    pub fn checked_pow(self, mut exponent: u128) -> MathResult<Self> {
+        let mut result = Decimal::ONE;
+        let mut base = self;
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result.checked_mul(base, Rounding::Down)?;
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = base.checked_mul(base, Rounding::Down)?;
+            }
+        }
+        Ok(result)
+    }
+
+    /// `amount * self`, e.g. shares to underlying at an exchange rate
+    pub fn mul_amount(self, amount: u128, rounding: Rounding) -> MathResult<u128> {
+        mul_div(amount, self.0, SCALE, rounding)
+    }
+
+    /// `amount / self`, e.g. underlying to shares at an exchange rate
+    pub fn div_amount(self, amount: u128, rounding: Rounding) -> MathResult<u128> {
+        mul_div(amount, SCALE, self.0, rounding)
+    }
+}
+
+/// `annual_rate * seconds / SECONDS_PER_YEAR` without truncating to a per-second rate first
+pub fn accrued_rate(annual_rate: Decimal, seconds: u64) -> MathResult<Decimal> {
+    Ok(Decimal(mul_div(annual_rate.0, seconds as u128, SECONDS_PER_YEAR as u128, Rounding::Down)?))
+}
+
+/// Largest exponent fed to the Taylor terms in one step (0.1)
+const MAX_TAYLOR_STEP: u128 = SCALE / 10;
+
+/// Continuously compounded growth factor `e^(annual_rate * t)` for `seconds` elapsed,
+/// rounded down. `x = annual_rate * t` is split into `n` equal steps of at most 0.1 and
+/// each step uses the first four Taylor terms, so `e^x = e^(x/n)^n` stays accurate after
+/// long gaps or at high rates: within 1e-5 below `e^x` for `x <= 0.1`, and within about
+/// `5e-5 * x` relative error beyond that.
+pub fn compound_growth(annual_rate: Decimal, seconds: u64) -> MathResult<Decimal> {
+    let x = accrued_rate(annual_rate, seconds)?;
+    let steps = x.0.div_ceil(MAX_TAYLOR_STEP).max(1);
+    let step = Decimal(x.0 / steps);
+    let remainder = Decimal(x.0 % steps);
+    taylor_exp(step)?
+        .checked_pow(steps)?
+        .checked_mul(taylor_exp(remainder)?, Rounding::Down)
+}
+
+/// `1 + x + x^2/2 + x^3/6`, rounded down
+fn taylor_exp(x: Decimal) -> MathResult<Decimal> {
+    let x2 = x.checked_mul(x, Rounding::Down)?;
+    let x3 = x2.checked_mul(x, Rounding::Down)?;
+    Decimal::ONE
+        .checked_add(x)?
+        .checked_add(Decimal(x2.0 / 2))?
+        .checked_add(Decimal(x3.0 / 6))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const YEAR: u64 = SECONDS_PER_YEAR;
+
+    #[test]
+    fn mul_div_rounds_in_requested_direction() {
+        assert_eq!(mul_div(10, 1, 3, Rounding::Down), Ok(3));
+        assert_eq!(mul_div(10, 1, 3, Rounding::Up), Ok(4));
+        // exact quotients are unaffected by the rounding direction
+        assert_eq!(mul_div(9, 1, 3, Rounding::Down), Ok(3));
+        assert_eq!(mul_div(9, 1, 3, Rounding::Up), Ok(3));
+        assert_eq!(mul_div(0, 5, 3, Rounding::Up), Ok(0));
+        // u128 intermediate: a * b exceeds u64 but the quotient fits
+        assert_eq!(mul_div(u64::MAX as u128, 1_000, 1_000, Rounding::Down), Ok(u64::MAX as u128));
+    }
+
+    #[test]
+    fn mul_div_overflow_and_zero_denominator() {
+        assert_eq!(mul_div(u128::MAX, 2, 1, Rounding::Down), Err(MathError::Overflow));
+        assert_eq!(mul_div(1, 1, 0, Rounding::Down), Err(MathError::DivideByZero));
+        assert_eq!(mul_div(1, 1, 0, Rounding::Up), Err(MathError::DivideByZero));
+    }
+
+    #[test]
+    fn bps_of_amounts() {
+        assert_eq!(bps_of(10_000, 30, Rounding::Down), Ok(30));
+        assert_eq!(bps_of(1_000_000, 10_000, Rounding::Down), Ok(1_000_000));
+        assert_eq!(bps_of(1, 5_000, Rounding::Down), Ok(0));
+        assert_eq!(bps_of(1, 5_000, Rounding::Up), Ok(1));
+        assert_eq!(bps_of(12_345, 0, Rounding::Up), Ok(0));
+        assert_eq!(bps_of(u128::MAX, 2, Rounding::Down), Err(MathError::Overflow));
+    }
+
+    #[test]
+    fn to_u64_narrowing() {
+        assert_eq!(to_u64(u64::MAX as u128), Ok(u64::MAX));
+        assert_eq!(to_u64(u64::MAX as u128 + 1), Err(MathError::Overflow));
+    }
+
+    #[test]
+    fn decimal_conversions() {
+        assert_eq!(Decimal::from_bps(10_000), Decimal::ONE);
+        assert_eq!(Decimal::from_bps(2_500).to_scaled(), 250_000_000);
+        assert_eq!(Decimal::from_bps(123).to_bps(Rounding::Down), Ok(123));
+        assert_eq!(Decimal::from_scaled(1).to_bps(Rounding::Down), Ok(0));
+        assert_eq!(Decimal::from_scaled(1).to_bps(Rounding::Up), Ok(1));
+        assert_eq!(Decimal::from_ratio(1, 3, Rounding::Down).unwrap().to_scaled(), 333_333_333);
+        assert_eq!(Decimal::from_ratio(1, 3, Rounding::Up).unwrap().to_scaled(), 333_333_334);
+        assert_eq!(Decimal::from_ratio(1, 0, Rounding::Down), Err(MathError::DivideByZero));
+    }
+
+    #[test]
+    fn decimal_arithmetic() {
+        let half = Decimal::from_bps(5_000);
+        let two = Decimal::ONE.checked_add(Decimal::ONE).unwrap();
+        assert_eq!(half.checked_mul(two, Rounding::Down), Ok(Decimal::ONE));
+        assert_eq!(Decimal::ONE.checked_div(two, Rounding::Down), Ok(half));
+        assert_eq!(two.checked_sub(Decimal::ONE), Ok(Decimal::ONE));
+        assert_eq!(Decimal::ZERO.checked_sub(Decimal::ONE), Err(MathError::Overflow));
+        assert_eq!(Decimal::from_scaled(u128::MAX).checked_add(Decimal::ONE), Err(MathError::Overflow));
+        assert_eq!(Decimal::ONE.checked_div(Decimal::ZERO, Rounding::Down), Err(MathError::DivideByZero));
+
+        // exchange-rate style conversions round as requested
+        let third = Decimal::from_ratio(4, 3, Rounding::Down).unwrap();
+        assert_eq!(third.mul_amount(3, Rounding::Down), Ok(3));
+        assert_eq!(third.mul_amount(3, Rounding::Up), Ok(4));
+        assert_eq!(two.div_amount(5, Rounding::Down), Ok(2));
+        assert_eq!(two.div_amount(5, Rounding::Up), Ok(3));
+    }
+
+    #[test]
+    fn accrued_rate_is_pro_rata() {
+        let ten_pct = Decimal::from_bps(1_000);
+        assert_eq!(accrued_rate(ten_pct, YEAR), Ok(ten_pct));
+        assert_eq!(accrued_rate(ten_pct, YEAR / 2), Ok(Decimal::from_bps(500)));
+        assert_eq!(accrued_rate(ten_pct, 0), Ok(Decimal::ZERO));
+    }
+
+    #[test]
+    fn compound_growth_matches_taylor_expansion() {
+        // x = 0.1: 1 + 0.1 + 0.005 + 0.000166666
+        let ten_pct = Decimal::from_bps(1_000);
+        assert_eq!(compound_growth(ten_pct, YEAR).unwrap().to_scaled(), 1_105_166_666);
+        // x = 0.025: 1 + 0.025 + 0.0003125 + 0.000002604
+        let five_pct = Decimal::from_bps(500);
+        assert_eq!(compound_growth(five_pct, YEAR / 2).unwrap().to_scaled(), 1_025_315_104);
+        assert_eq!(compound_growth(ten_pct, 0), Ok(Decimal::ONE));
+        assert_eq!(compound_growth(Decimal::ZERO, YEAR), Ok(Decimal::ONE));
+    }
+
+    #[test]
+    fn compound_growth_within_documented_error_of_exp() {
+        for (bps, seconds) in [(500u64, 3_600u64), (2_000, 86_400), (10_000, YEAR / 12)] {
+            // < 1e-5 (1e4 scaled units) below e^x while x stays under ~0.1
+            let x = bps as f64 / 10_000.0 * seconds as f64 / YEAR as f64;
+            let expected = (x.exp() * SCALE as f64) as i128;
+            let got = compound_growth(Decimal::from_bps(bps), seconds).unwrap().to_scaled() as i128;
+            assert!((expected - got).abs() <= 10_000, "bps {bps} seconds {seconds}: {got} vs {expected}");
+            // never above the true value, so borrowers are not overcharged
+            assert!(got <= expected + 1);
+        }
+    }
+
+    #[test]
+    fn decimal_pow() {
+        let two = Decimal::ONE.checked_add(Decimal::ONE).unwrap();
+        assert_eq!(two.checked_pow(0), Ok(Decimal::ONE));
+        assert_eq!(two.checked_pow(1), Ok(two));
+        assert_eq!(two.checked_pow(10).unwrap().to_scaled(), 1_024 * SCALE);
+        assert_eq!(Decimal::from_bps(5_000).checked_pow(3).unwrap().to_scaled(), 125_000_000);
+        assert_eq!(two.checked_pow(128), Err(MathError::Overflow));
+    }
+
+    #[test]
+    fn compound_growth_after_long_gap_at_high_rate() {
+        // 100% for a year: e = 2.718281828
+        let hundred_pct = Decimal::from_bps(10_000);
+        let got = compound_growth(hundred_pct, YEAR).unwrap().to_scaled();
+        assert!((2_718_150_000..=2_718_281_828).contains(&got), "{got}");
+
+        // 200% for ten years: e^20 = 485165195.4097903
+        let two_hundred_pct = Decimal::from_bps(20_000);
+        let got = compound_growth(two_hundred_pct, 10 * YEAR).unwrap().to_scaled() as f64 / SCALE as f64;
+        let expected = 20f64.exp();
+        assert!(got <= expected && (expected - got) / expected < 1e-3, "{got} vs {expected}");
+    }
+
+    #[test]
+    fn compound_growth_overflow() {
+        assert_eq!(compound_growth(Decimal::from_scaled(u128::MAX), YEAR), Err(MathError::Overflow));
+    }
+}
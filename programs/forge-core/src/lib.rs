@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Mint, Transfer, MintTo};
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
 
 declare_id!("DWkDGw5Pvqgh3DN6HZwssn31AUAkuWLtjDnjyEUdgRHU");
 
@@ -50,6 +50,7 @@ pub mod forge_core {
         let crucible_registry = &mut ctx.accounts.crucible_registry;
         let forge_protocol = &mut ctx.accounts.forge_protocol;
 
+        require!(forge_protocol.is_active, ForgeError::ProtocolInactive);
         require!(forge_protocol.crucible_count < forge_protocol.max_crucibles, ForgeError::MaxCruciblesReached);
 
         crucible_registry.id = crucible_id;
@@ -65,21 +66,26 @@ pub mod forge_core {
         Ok(())
     }
 
-    /// Collect protocol fees to treasury
+    /// Collect protocol fees to treasury.
+    /// Fee vaults are owned by the protocol PDA, which signs the sweep.
     pub fn collect_fees(
         ctx: Context<CollectFees>,
         amount: u64,
     ) -> Result<()> {
+        let seeds = &[b"forge_protocol".as_ref(), &[ctx.accounts.forge_protocol.bump]];
+        let signer = &[&seeds[..]];
+
         let cpi_accounts = Transfer {
             from: ctx.accounts.fee_vault.to_account_info(),
             to: ctx.accounts.treasury.to_account_info(),
-            authority: ctx.accounts.authority.to_account_info(),
+            authority: ctx.accounts.forge_protocol.to_account_info(),
         };
 
         token::transfer(
-            CpiContext::new(
+            CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
                 cpi_accounts,
+                signer,
             ),
             amount,
         )?;
@@ -148,11 +154,12 @@ pub struct RegisterCrucible<'info> {
 
 #[derive(Accounts)]
 pub struct CollectFees<'info> {
-    #[account(mut, has_one = authority)]
+    #[account(mut, has_one = authority, seeds = [b"forge_protocol"], bump = forge_protocol.bump)]
     pub forge_protocol: Account<'info, ForgeProtocol>,
-    #[account(mut)]
+    #[account(mut, token::authority = forge_protocol)]
     pub fee_vault: Account<'info, TokenAccount>,
-    #[account(mut)]
+    /// Treasury-owned token account for the fee vault's mint
+    #[account(mut, token::mint = fee_vault.mint, token::authority = forge_protocol.treasury)]
     pub treasury: Account<'info, TokenAccount>,
     pub authority: Signer<'info>,
     pub token_program: Program<'info, Token>,
@@ -0,0 +1,373 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use forge_core::{ForgeError, ForgeProtocol};
+use forge_math::BPS_SCALE;
+use forge_reactors::state::Pool;
+use oracle::state::PriceFeed;
+
+use crate::ctoken::CrucibleError;
+use crate::state::*;
+
+/// Create a crucible for `base_mint` with its vault and cToken mint.
+/// The crucible account is the `[b"crucible", base_mint]` PDA that owns both.
+/// Only the forge_core protocol authority may create crucibles; it becomes the crucible authority.
+pub fn initialize_crucible(ctx: Context<InitializeCrucible>, fee_rate: u64) -> Result<()> {
+    require!(fee_rate <= MAX_FEE_RATE, CrucibleError::InvalidFeeRate);
+
+    let crucible = &mut ctx.accounts.crucible;
+    let clock = Clock::get()?;
+
+    crucible.base_mint = ctx.accounts.base_mint.key();
+    crucible.ctoken_mint = ctx.accounts.ctoken_mint.key();
+    crucible.vault = ctx.accounts.vault.key();
+    crucible.vault_bump = ctx.bumps.vault;
+    crucible.bump = ctx.bumps.crucible;
+    crucible.total_base_deposited = 0;
+    crucible.total_ctoken_supply = 0;
+    crucible.last_update_slot = clock.slot;
+    crucible.fee_rate = fee_rate;
+    crucible.paused = false;
+    crucible.total_leveraged_positions = 0;
+    crucible.total_lp_positions = 0;
+    crucible.oracle = ctx.accounts.price_feed.key();
+    crucible.lp_position_nonce = 0;
+    crucible.lp_pool = Pubkey::default();
+    crucible.authority = ctx.accounts.authority.key();
+    crucible.pause_flags = 0;
+    crucible.forge_protocol = ctx.accounts.forge_protocol.key();
+    crucible.fee_vault = Pubkey::default();
+    crucible.lvf_liquidation_threshold_bps = DEFAULT_LVF_LIQUIDATION_THRESHOLD_BPS;
+    crucible.lvf_liquidation_bonus_bps = DEFAULT_LVF_LIQUIDATION_BONUS_BPS;
+    crucible.lvf_max_liquidation_slippage_bps = DEFAULT_LVF_MAX_LIQUIDATION_SLIPPAGE_BPS;
+
+    emit!(CrucibleInitialized {
+        crucible: crucible.key(),
+        base_mint: crucible.base_mint,
+        ctoken_mint: crucible.ctoken_mint,
+        authority: crucible.authority,
+        fee_rate,
+    });
+
+    Ok(())
+}
+
+/// Update fee rate and pause flag; optionally re-point the oracle or LP pool.
+/// The LP pool can only change while no LP positions hold shares of the current one.
+pub fn update_crucible_config(
+    ctx: Context<UpdateCrucibleConfig>,
+    fee_rate: u64,
+    paused: bool,
+) -> Result<()> {
+    require!(fee_rate <= MAX_FEE_RATE, CrucibleError::InvalidFeeRate);
+
+    let crucible = &mut ctx.accounts.crucible;
+    crucible.fee_rate = fee_rate;
+    crucible.paused = paused;
+
+    if let Some(price_feed) = &ctx.accounts.price_feed {
+        require!(price_feed.mint == crucible.base_mint, CrucibleError::InvalidBaseMint);
+        crucible.oracle = price_feed.key();
+    }
+    if let Some(lp_pool) = &ctx.accounts.lp_pool {
+        require!(lp_pool.token_a_mint == crucible.base_mint, CrucibleError::InvalidBaseMint);
+        require!(
+            lp_pool.key() == crucible.lp_pool || crucible.total_lp_positions == 0,
+            CrucibleError::LPPositionsOpen
+        );
+        crucible.lp_pool = lp_pool.key();
+    }
+    if let Some(forge_protocol) = &ctx.accounts.forge_protocol {
+        crucible.forge_protocol = forge_protocol.key();
+    }
+
+    emit!(CrucibleConfigUpdated {
+        crucible: crucible.key(),
+        fee_rate,
+        paused,
+        oracle: crucible.oracle,
+        lp_pool: crucible.lp_pool,
+    });
+
+    Ok(())
+}
+
+/// Set when leveraged positions become liquidatable, what the liquidator earns and how far
+/// below the oracle price a liquidation may sell base
+pub fn update_lvf_config(
+    ctx: Context<UpdateLvfConfig>,
+    liquidation_threshold_bps: u64,
+    liquidation_bonus_bps: u64,
+    max_liquidation_slippage_bps: u64,
+) -> Result<()> {
+    require!(
+        liquidation_threshold_bps > 0 && liquidation_threshold_bps as u128 <= BPS_SCALE,
+        CrucibleError::InvalidLvfConfig
+    );
+    require!(liquidation_bonus_bps <= MAX_LVF_LIQUIDATION_BONUS_BPS, CrucibleError::InvalidLvfConfig);
+    require!(
+        max_liquidation_slippage_bps <= MAX_LVF_LIQUIDATION_SLIPPAGE_BPS,
+        CrucibleError::InvalidLvfConfig
+    );
+
+    let crucible = &mut ctx.accounts.crucible;
+    crucible.lvf_liquidation_threshold_bps = liquidation_threshold_bps;
+    crucible.lvf_liquidation_bonus_bps = liquidation_bonus_bps;
+    crucible.lvf_max_liquidation_slippage_bps = max_liquidation_slippage_bps;
+
+    emit!(CrucibleLvfConfigUpdated {
+        crucible: crucible.key(),
+        liquidation_threshold_bps,
+        liquidation_bonus_bps,
+        max_liquidation_slippage_bps,
+    });
+
+    Ok(())
+}
+
+/// Pause or resume the crucible
+pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+    let crucible = &mut ctx.accounts.crucible;
+    crucible.paused = paused;
+
+    emit!(CruciblePausedUpdated {
+        crucible: crucible.key(),
+        paused,
+    });
+
+    Ok(())
+}
+
+/// Create the treasury fee vault for a crucible.
+/// The vault is owned by the forge_core protocol PDA so `collect_fees` can sweep it.
+pub fn initialize_fee_vault(ctx: Context<InitializeFeeVault>) -> Result<()> {
+    let crucible = &mut ctx.accounts.crucible;
+    crucible.fee_vault = ctx.accounts.fee_vault.key();
+    crucible.forge_protocol = ctx.accounts.forge_protocol.key();
+
+    emit!(CrucibleFeeVaultInitialized {
+        crucible: crucible.key(),
+        fee_vault: crucible.fee_vault,
+        forge_protocol: crucible.forge_protocol,
+    });
+
+    Ok(())
+}
+
+/// Block or unblock individual scopes (`PAUSE_DEPOSITS`, `PAUSE_WITHDRAWALS`, `PAUSE_LEVERAGE`)
+pub fn set_pause_flags(ctx: Context<SetPaused>, pause_flags: u8) -> Result<()> {
+    require!(pause_flags & !PAUSE_ALL == 0, CrucibleError::InvalidPauseFlags);
+
+    let crucible = &mut ctx.accounts.crucible;
+    crucible.pause_flags = pause_flags;
+
+    emit!(CruciblePauseFlagsUpdated {
+        crucible: crucible.key(),
+        pause_flags,
+    });
+
+    Ok(())
+}
+
+/// Circuit breaker shared by all user instructions.
+/// Fails if the crucible is paused, `scope` is paused, or the linked protocol is inactive.
+pub fn require_active(
+    crucible: &Crucible,
+    forge_protocol: Option<&ForgeProtocol>,
+    scope: u8,
+) -> Result<()> {
+    require_not_halted(crucible, forge_protocol)?;
+    if crucible.pause_flags & scope != 0 {
+        return Err(match scope {
+            PAUSE_DEPOSITS => CrucibleError::DepositsPaused,
+            PAUSE_WITHDRAWALS => CrucibleError::WithdrawalsPaused,
+            _ => CrucibleError::LeveragePaused,
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Scope-independent part of `require_active`: fails only if the whole crucible is
+/// paused or the linked protocol is inactive. Used by liquidations, which must keep
+/// working while user withdrawals are paused.
+pub fn require_not_halted(crucible: &Crucible, forge_protocol: Option<&ForgeProtocol>) -> Result<()> {
+    require!(!crucible.paused, CrucibleError::CruciblePaused);
+    match forge_protocol {
+        Some(protocol) => require!(protocol.is_active, ForgeError::ProtocolInactive),
+        None => require!(
+            crucible.forge_protocol == Pubkey::default(),
+            CrucibleError::MissingProtocolAccount
+        ),
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeCrucible<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"forge_protocol"],
+        bump = forge_protocol.bump,
+        seeds::program = forge_core::ID,
+        has_one = authority @ CrucibleError::Unauthorized,
+    )]
+    pub forge_protocol: Account<'info, ForgeProtocol>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Crucible::LEN,
+        seeds = [b"crucible", base_mint.key().as_ref()],
+        bump,
+    )]
+    pub crucible: Account<'info, Crucible>,
+
+    pub base_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"ctoken_mint", crucible.key().as_ref()],
+        bump,
+        mint::decimals = base_mint.decimals,
+        mint::authority = crucible,
+    )]
+    pub ctoken_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"vault", crucible.key().as_ref()],
+        bump,
+        token::mint = base_mint,
+        token::authority = crucible,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(constraint = price_feed.mint == base_mint.key() @ CrucibleError::InvalidBaseMint)]
+    pub price_feed: Account<'info, PriceFeed>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateCrucibleConfig<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority @ CrucibleError::Unauthorized,
+    )]
+    pub crucible: Account<'info, Crucible>,
+
+    pub price_feed: Option<Account<'info, PriceFeed>>,
+
+    pub lp_pool: Option<Account<'info, Pool>>,
+
+    #[account(seeds = [b"forge_protocol"], bump = forge_protocol.bump, seeds::program = forge_core::ID)]
+    pub forge_protocol: Option<Account<'info, ForgeProtocol>>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateLvfConfig<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority @ CrucibleError::Unauthorized,
+    )]
+    pub crucible: Account<'info, Crucible>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeFeeVault<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority @ CrucibleError::Unauthorized,
+        has_one = base_mint @ CrucibleError::InvalidBaseMint,
+    )]
+    pub crucible: Account<'info, Crucible>,
+
+    pub base_mint: Account<'info, Mint>,
+
+    #[account(seeds = [b"forge_protocol"], bump = forge_protocol.bump, seeds::program = forge_core::ID)]
+    pub forge_protocol: Account<'info, ForgeProtocol>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"fee_vault", crucible.key().as_ref()],
+        bump,
+        token::mint = base_mint,
+        token::authority = forge_protocol,
+    )]
+    pub fee_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority @ CrucibleError::Unauthorized,
+    )]
+    pub crucible: Account<'info, Crucible>,
+}
+
+#[event]
+pub struct CrucibleInitialized {
+    pub crucible: Pubkey,
+    pub base_mint: Pubkey,
+    pub ctoken_mint: Pubkey,
+    pub authority: Pubkey,
+    pub fee_rate: u64,
+}
+
+#[event]
+pub struct CrucibleConfigUpdated {
+    pub crucible: Pubkey,
+    pub fee_rate: u64,
+    pub paused: bool,
+    pub oracle: Pubkey,
+    pub lp_pool: Pubkey,
+}
+
+#[event]
+pub struct CrucibleLvfConfigUpdated {
+    pub crucible: Pubkey,
+    pub liquidation_threshold_bps: u64,
+    pub liquidation_bonus_bps: u64,
+    pub max_liquidation_slippage_bps: u64,
+}
+
+#[event]
+pub struct CruciblePausedUpdated {
+    pub crucible: Pubkey,
+    pub paused: bool,
+}
+
+#[event]
+pub struct CrucibleFeeVaultInitialized {
+    pub crucible: Pubkey,
+    pub fee_vault: Pubkey,
+    pub forge_protocol: Pubkey,
+}
+
+#[event]
+pub struct CruciblePauseFlagsUpdated {
+    pub crucible: Pubkey,
+    pub pause_flags: u8,
+}
@@ -1,26 +1,38 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Mint, Token, TokenAccount, MintTo, Burn};
 use anchor_spl::associated_token::AssociatedToken;
+use forge_core::ForgeProtocol;
+use forge_math::{to_u64, Decimal, Rounding};
+use crate::crucible::require_active;
 use crate::state::*;
 
 /// Mint cToken when user deposits base token
-pub fn mint_ctoken(ctx: Context<MintCToken>, amount: u64) -> Result<()> {
+pub fn mint_ctoken(ctx: Context<MintCToken>, amount: u64, min_ctokens_out: u64) -> Result<()> {
+    require_active(&ctx.accounts.crucible, ctx.accounts.forge_protocol.as_deref(), PAUSE_DEPOSITS)?;
+
     let crucible = &mut ctx.accounts.crucible;
     let clock = Clock::get()?;
     
     // Calculate exchange rate (1 cToken = base_amount / total_ctoken_supply)
     // Exchange rate grows as fees accrue
     let exchange_rate = calculate_exchange_rate(
-        &crucible,
+        crucible,
         ctx.accounts.vault.amount,
         ctx.accounts.ctoken_mint.supply,
     )?;
     
-    // Calculate how many cTokens to mint based on current exchange rate
-    let ctokens_to_mint = amount
-        .checked_mul(1_000_000u64) // Scale for precision
-        .and_then(|scaled| scaled.checked_div(exchange_rate))
-        .ok_or(ProgramError::InvalidArgument)?;
+    // Deposit fee: holder share stays in the vault (raising the rate), treasury share is routed out
+    let (holder_fee, treasury_fee) = fee_split_for(crucible, &ctx.accounts.fee_vault, amount)?;
+    let net_amount = amount - holder_fee - treasury_fee;
+
+    // Calculate how many cTokens to mint against the pre-deposit vault balance
+    let ctokens_to_mint = ctokens_for_deposit(
+        net_amount,
+        ctx.accounts.vault.amount,
+        ctx.accounts.ctoken_mint.supply,
+    )?;
+    require!(ctokens_to_mint > 0, CrucibleError::ZeroCTokens);
+    require!(ctokens_to_mint >= min_ctokens_out, CrucibleError::SlippageExceeded);
     
     // Transfer base tokens from user to vault
     let cpi_accounts = token::Transfer {
@@ -30,7 +42,17 @@ pub fn mint_ctoken(ctx: Context<MintCToken>, amount: u64) -> Result<()> {
     };
     let cpi_program = ctx.accounts.token_program.to_account_info();
     let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-    token::transfer(cpi_ctx, amount)?;
+    token::transfer(cpi_ctx, amount - treasury_fee)?;
+
+    if let (Some(fee_vault), true) = (&ctx.accounts.fee_vault, treasury_fee > 0) {
+        let cpi_accounts = token::Transfer {
+            from: ctx.accounts.user_token_account.to_account_info(),
+            to: fee_vault.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::transfer(CpiContext::new(cpi_program, cpi_accounts), treasury_fee)?;
+    }
     
     // Mint cTokens to user
     let seeds = &[
@@ -52,7 +74,7 @@ pub fn mint_ctoken(ctx: Context<MintCToken>, amount: u64) -> Result<()> {
     // Update crucible state
     crucible.total_base_deposited = crucible
         .total_base_deposited
-        .checked_add(amount)
+        .checked_add(amount - treasury_fee)
         .ok_or(ProgramError::ArithmeticOverflow)?;
     crucible.last_update_slot = clock.slot;
     
@@ -62,33 +84,43 @@ pub fn mint_ctoken(ctx: Context<MintCToken>, amount: u64) -> Result<()> {
         amount,
         ctokens_minted: ctokens_to_mint,
         exchange_rate,
+        holder_fee,
+        treasury_fee,
     });
     
     Ok(())
 }
 
 /// Burn cToken and return base tokens to user
-pub fn burn_ctoken(ctx: Context<BurnCToken>, ctokens_amount: u64) -> Result<()> {
+pub fn burn_ctoken(ctx: Context<BurnCToken>, ctokens_amount: u64, min_base_out: u64) -> Result<()> {
+    require_active(&ctx.accounts.crucible, ctx.accounts.forge_protocol.as_deref(), PAUSE_WITHDRAWALS)?;
+
     let crucible = &mut ctx.accounts.crucible;
     let clock = Clock::get()?;
     
     // Calculate current exchange rate
     let exchange_rate = calculate_exchange_rate(
-        &crucible,
+        crucible,
         ctx.accounts.vault.amount,
         ctx.accounts.ctoken_mint.supply,
     )?;
     
     // Calculate base tokens to return (includes accrued yield)
-    let base_to_return = ctokens_amount
-        .checked_mul(exchange_rate)
-        .and_then(|scaled| scaled.checked_div(1_000_000u64))
-        .ok_or(ProgramError::InvalidArgument)?;
+    let base_to_return = base_for_ctokens(
+        ctokens_amount,
+        ctx.accounts.vault.amount,
+        ctx.accounts.ctoken_mint.supply,
+    )?;
     
     require!(
         base_to_return <= ctx.accounts.vault.amount,
         CrucibleError::InsufficientLiquidity
     );
+
+    // Withdrawal fee is taken from the redeemed amount
+    let (holder_fee, treasury_fee) = fee_split_for(crucible, &ctx.accounts.fee_vault, base_to_return)?;
+    let user_amount = base_to_return - holder_fee - treasury_fee;
+    require!(user_amount >= min_base_out, CrucibleError::SlippageExceeded);
     
     // Burn user's cTokens
     let seeds = &[
@@ -115,41 +147,63 @@ pub fn burn_ctoken(ctx: Context<BurnCToken>, ctokens_amount: u64) -> Result<()>
     };
     let cpi_program = ctx.accounts.token_program.to_account_info();
     let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
-    token::transfer(cpi_ctx, base_to_return)?;
+    token::transfer(cpi_ctx, user_amount)?;
+
+    if let (Some(fee_vault), true) = (&ctx.accounts.fee_vault, treasury_fee > 0) {
+        let cpi_accounts = token::Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: fee_vault.to_account_info(),
+            authority: ctx.accounts.crucible_authority.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer), treasury_fee)?;
+    }
     
-    // Update crucible state
-    crucible.total_base_deposited = crucible
-        .total_base_deposited
-        .checked_sub(base_to_return.min(crucible.total_base_deposited))
-        .unwrap_or(0);
+    // Update crucible state (holder fee remains in the vault)
+    let withdrawn = user_amount + treasury_fee;
+    crucible.total_base_deposited = crucible.total_base_deposited.saturating_sub(withdrawn);
     crucible.last_update_slot = clock.slot;
     
     emit!(CTokenBurned {
         crucible: crucible.key(),
         user: ctx.accounts.user.key(),
         ctokens_burned: ctokens_amount,
-        base_returned: base_to_return,
+        base_returned: user_amount,
         exchange_rate,
+        holder_fee,
+        treasury_fee,
     });
     
     Ok(())
 }
 
-/// Calculate exchange rate: vault_amount / ctoken_supply (scaled by 1M for precision)
-fn calculate_exchange_rate(
+/// Fee split for `amount`; without a fee vault the treasury share also stays with holders
+pub(crate) fn fee_split_for(
+    crucible: &Crucible,
+    fee_vault: &Option<Account<TokenAccount>>,
+    amount: u64,
+) -> Result<(u64, u64)> {
+    let (holder_fee, treasury_fee) = split_fee(amount, crucible.fee_rate)?;
+    if crucible.fee_vault == Pubkey::default() {
+        return Ok((holder_fee + treasury_fee, 0));
+    }
+    require!(fee_vault.is_some(), CrucibleError::MissingFeeVault);
+    Ok((holder_fee, treasury_fee))
+}
+
+/// Calculate exchange rate including virtual shares/assets (forge_math::SCALE, informational only)
+pub(crate) fn calculate_exchange_rate(
     _crucible: &Crucible,
     vault_amount: u64,
     ctoken_supply: u64,
 ) -> Result<u64> {
-    if ctoken_supply == 0 {
-        // Initial exchange rate is 1:1
-        return Ok(1_000_000u64);
-    }
-    
-    vault_amount
-        .checked_mul(1_000_000u64)
-        .and_then(|scaled| scaled.checked_div(ctoken_supply))
-        .ok_or(ProgramError::ArithmeticOverflow.into())
+    // Initial exchange rate is 1:1
+    let rate = Decimal::from_ratio(
+        vault_amount as u128 + VIRTUAL_ASSETS as u128,
+        ctoken_supply as u128 + VIRTUAL_SHARES as u128,
+        Rounding::Down,
+    )?;
+    Ok(to_u64(rate.to_scaled())?)
 }
 
 #[derive(Accounts)]
@@ -164,7 +218,7 @@ pub struct MintCToken<'info> {
     pub crucible: Account<'info, Crucible>,
     
     pub base_mint: Account<'info, Mint>,
-    #[account(mut)]
+    #[account(mut, address = crucible.ctoken_mint @ CrucibleError::InvalidMint)]
     pub ctoken_mint: Account<'info, Mint>,
     
     #[account(mut)]
@@ -196,6 +250,12 @@ pub struct MintCToken<'info> {
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
+
+    #[account(seeds = [b"forge_protocol"], bump = forge_protocol.bump, seeds::program = forge_core::ID)]
+    pub forge_protocol: Option<Account<'info, ForgeProtocol>>,
+
+    #[account(mut, address = crucible.fee_vault)]
+    pub fee_vault: Option<Account<'info, TokenAccount>>,
 }
 
 #[derive(Accounts)]
@@ -210,7 +270,7 @@ pub struct BurnCToken<'info> {
     pub crucible: Account<'info, Crucible>,
     
     pub base_mint: Account<'info, Mint>,
-    #[account(mut)]
+    #[account(mut, address = crucible.ctoken_mint @ CrucibleError::InvalidMint)]
     pub ctoken_mint: Account<'info, Mint>,
     
     #[account(mut)]
@@ -235,6 +295,12 @@ pub struct BurnCToken<'info> {
     
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
+
+    #[account(seeds = [b"forge_protocol"], bump = forge_protocol.bump, seeds::program = forge_core::ID)]
+    pub forge_protocol: Option<Account<'info, ForgeProtocol>>,
+
+    #[account(mut, address = crucible.fee_vault)]
+    pub fee_vault: Option<Account<'info, TokenAccount>>,
 }
 
 #[event]
@@ -244,6 +310,8 @@ pub struct CTokenMinted {
     pub amount: u64,
     pub ctokens_minted: u64,
     pub exchange_rate: u64,
+    pub holder_fee: u64,
+    pub treasury_fee: u64,
 }
 
 #[event]
@@ -253,6 +321,8 @@ pub struct CTokenBurned {
     pub ctokens_burned: u64,
     pub base_returned: u64,
     pub exchange_rate: u64,
+    pub holder_fee: u64,
+    pub treasury_fee: u64,
 }
 
 #[error_code]
@@ -269,5 +339,39 @@ pub enum CrucibleError {
     Unauthorized,
     #[msg("Invalid position ID")]
     InvalidPosition,
+    #[msg("Invalid LP amounts - must be equal value")]
+    InvalidLPAmounts,
+    #[msg("Position not found")]
+    PositionNotFound,
+    #[msg("Fee rate exceeds maximum")]
+    InvalidFeeRate,
+    #[msg("Crucible is paused")]
+    CruciblePaused,
+    #[msg("Deposits are paused")]
+    DepositsPaused,
+    #[msg("Withdrawals are paused")]
+    WithdrawalsPaused,
+    #[msg("New leveraged positions are paused")]
+    LeveragePaused,
+    #[msg("Forge protocol account required")]
+    MissingProtocolAccount,
+    #[msg("Invalid pause flags")]
+    InvalidPauseFlags,
+    #[msg("Fee vault account required")]
+    MissingFeeVault,
+    #[msg("Deposit too small to mint any cTokens")]
+    ZeroCTokens,
+    #[msg("Slippage tolerance exceeded")]
+    SlippageExceeded,
+    #[msg("LP pool cannot change while LP positions are open")]
+    LPPositionsOpen,
+    #[msg("cToken mint does not belong to this crucible")]
+    InvalidMint,
+    #[msg("Position value does not cover its debt")]
+    PositionUnderwater,
+    #[msg("Position is not liquidatable")]
+    PositionHealthy,
+    #[msg("Invalid leveraged liquidation config")]
+    InvalidLvfConfig,
 }
 
@@ -1,65 +1,116 @@
 use anchor_lang::prelude::*;
 
+pub mod crucible;
 pub mod ctoken;
 pub mod lvf;
 pub mod lp;
 pub mod state;
 
+use crucible::*;
 use ctoken::*;
 use lvf::*;
 use lp::*;
-use state::*;
 
-declare_id!("Crucible111111111111111111111111111111111");
+declare_id!("Ab84n2rkgEnDnQmJKfMsr88jbJqYPcgBW7irwoYWwCL2");
 
 #[program]
 pub mod forge_crucibles {
     use super::*;
 
-    // Existing crucible initialization (keep your existing implementation)
-    // pub fn initialize_crucible(ctx: Context<InitializeCrucible>, ...) -> Result<()> { ... }
+    /// Create a crucible, its base vault and cToken mint
+    pub fn initialize_crucible(ctx: Context<InitializeCrucible>, fee_rate: u64) -> Result<()> {
+        crucible::initialize_crucible(ctx, fee_rate)
+    }
+
+    /// Update crucible fee rate and pause flag (authority only)
+    pub fn update_crucible_config(
+        ctx: Context<UpdateCrucibleConfig>,
+        fee_rate: u64,
+        paused: bool,
+    ) -> Result<()> {
+        crucible::update_crucible_config(ctx, fee_rate, paused)
+    }
+
+    /// Set the leveraged position liquidation threshold, bonus and swap slippage (authority only)
+    pub fn update_lvf_config(
+        ctx: Context<UpdateLvfConfig>,
+        liquidation_threshold_bps: u64,
+        liquidation_bonus_bps: u64,
+        max_liquidation_slippage_bps: u64,
+    ) -> Result<()> {
+        crucible::update_lvf_config(ctx, liquidation_threshold_bps, liquidation_bonus_bps, max_liquidation_slippage_bps)
+    }
+
+    /// Pause or resume a crucible (authority only)
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        crucible::set_paused(ctx, paused)
+    }
+
+    /// Create the treasury fee vault swept by forge_core (authority only)
+    pub fn initialize_fee_vault(ctx: Context<InitializeFeeVault>) -> Result<()> {
+        crucible::initialize_fee_vault(ctx)
+    }
+
+    /// Pause individual scopes: deposits, withdrawals, new leverage (authority only)
+    pub fn set_pause_flags(ctx: Context<SetPaused>, pause_flags: u8) -> Result<()> {
+        crucible::set_pause_flags(ctx, pause_flags)
+    }
 
     /// Mint cToken when user deposits base token
-    pub fn mint_ctoken(ctx: Context<MintCToken>, amount: u64) -> Result<()> {
-        ctoken::mint_ctoken(ctx, amount)
+    pub fn mint_ctoken(ctx: Context<MintCToken>, amount: u64, min_ctokens_out: u64) -> Result<()> {
+        ctoken::mint_ctoken(ctx, amount, min_ctokens_out)
     }
 
     /// Burn cToken and return base tokens to user
-    pub fn burn_ctoken(ctx: Context<BurnCToken>, ctokens_amount: u64) -> Result<()> {
-        ctoken::burn_ctoken(ctx, ctokens_amount)
+    pub fn burn_ctoken(ctx: Context<BurnCToken>, ctokens_amount: u64, min_base_out: u64) -> Result<()> {
+        ctoken::burn_ctoken(ctx, ctokens_amount, min_base_out)
     }
 
-    /// Open a leveraged LP position (TOKEN/USDC)
+    /// Open a leveraged position: borrowed USDC buys more base, all deposited for cTokens
     pub fn open_leveraged_position(
         ctx: Context<OpenLeveragedPosition>,
         collateral_amount: u64,
         leverage_factor: u64,
+        min_base_out: u64,
     ) -> Result<u64> {
-        lvf::open_leveraged_position(ctx, collateral_amount, leverage_factor)
+        lvf::open_leveraged_position(ctx, collateral_amount, leverage_factor, min_base_out)
     }
 
-    /// Close a leveraged LP position
+    /// Close a leveraged position: redeem its cTokens and sell base to repay the loan
     pub fn close_leveraged_position(
         ctx: Context<CloseLeveragedPosition>,
-        position_id: Pubkey,
+        position_id: u64,
+        min_base_out: u64,
+    ) -> Result<()> {
+        lvf::close_leveraged_position(ctx, position_id, min_base_out)
+    }
+
+    /// Liquidate an unhealthy leveraged position (permissionless)
+    pub fn liquidate_leveraged_position(
+        ctx: Context<LiquidateLeveragedPosition>,
+        position_id: u64,
+        min_usdc_out: u64,
     ) -> Result<()> {
-        lvf::close_leveraged_position(ctx, position_id)
+        lvf::liquidate_leveraged_position(ctx, position_id, min_usdc_out)
     }
 
-    /// Open a standard LP position (base token + USDC, equal value)
+    /// Open a standard LP position (base token + USDC, equal value) backed by pool shares
     pub fn open_lp_position(
         ctx: Context<OpenLPPosition>,
         base_amount: u64,
         usdc_amount: u64,
+        min_lp_shares: u64,
     ) -> Result<u64> {
-        lp::open_lp_position(ctx, base_amount, usdc_amount)
+        lp::open_lp_position(ctx, base_amount, usdc_amount, min_lp_shares)
     }
 
     /// Close a standard LP position
     pub fn close_lp_position(
         ctx: Context<CloseLPPosition>,
         position_id: u64,
+        min_base_out: u64,
+        min_usdc_out: u64,
     ) -> Result<()> {
-        lp::close_lp_position(ctx, position_id)
+        lp::close_lp_position(ctx, position_id, min_base_out, min_usdc_out)
     }
 }
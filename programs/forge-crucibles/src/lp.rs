@@ -1,116 +1,150 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Mint, Transfer};
+use anchor_spl::token::{Token, TokenAccount, Mint};
+use forge_core::ForgeProtocol;
+use forge_reactors::program::ForgeReactors;
+use forge_reactors::state::Pool;
 
-use crate::state::{Crucible, LPPosition};
-
-const RATE_SCALE: u128 = 1_000_000_000u128;
-
-#[error_code]
-pub enum CrucibleError {
-    #[msg("Invalid LP amounts - must be equal value")]
-    InvalidLPAmounts,
-    #[msg("Position not found")]
-    PositionNotFound,
-}
+use crate::crucible::require_active;
+use crate::ctoken::CrucibleError;
+use crate::state::{Crucible, LPPosition, PAUSE_DEPOSITS, PAUSE_WITHDRAWALS};
+use oracle::state::PriceFeed;
 
 pub fn open_lp_position(
     ctx: Context<OpenLPPosition>,
     base_amount: u64,
     usdc_amount: u64,
+    min_lp_shares: u64,
 ) -> Result<u64> {
-    let crucible = &mut ctx.accounts.crucible;
     let clock = Clock::get()?;
+    require_active(&ctx.accounts.crucible, ctx.accounts.forge_protocol.as_deref(), PAUSE_DEPOSITS)?;
 
     // Validate amounts are equal value (within tolerance)
-    // base_amount * base_price ≈ usdc_amount * 1.0
-    // In production, determine base token from base_mint or context
-    // For now, use a simplified check - in production you'd compare mint addresses
-    let base_token_price = 0.5; // Default FOGO price - in production, get from oracle or config
-
-    let base_value = (base_amount as u128)
-        .checked_mul((base_token_price * 1_000_000.0) as u128)
-        .unwrap()
-        .checked_div(1_000_000u128)
-        .unwrap();
-
-    let usdc_value = (usdc_amount as u128)
-        .checked_mul(1_000_000u128)
-        .unwrap()
-        .checked_div(1_000_000u128)
-        .unwrap();
+    // base_amount * base_price ≈ usdc_amount * 1.0, both in oracle::USD_DECIMALS
+    let price = oracle::get_price_default(&ctx.accounts.base_price_feed)?;
+    let base_token_price = price.scaled(oracle::USD_DECIMALS)?;
+    let base_decimals = ctx.accounts.base_mint.decimals;
+
+    let base_value = oracle::usd_value(base_amount as u128, base_decimals, &price)? as u128;
+
+    // USDC is valued at 1.0; `usdc_mint` is constrained to USD_DECIMALS
+    let usdc_value = usdc_amount as u128;
 
     // Allow 1% tolerance
     let tolerance = base_value
         .checked_mul(100)
-        .unwrap()
-        .checked_div(10000)
-        .unwrap();
+        .ok_or(ProgramError::ArithmeticOverflow)?
+        / 10000;
 
     require!(
-        base_value.checked_sub(usdc_value).unwrap().abs() <= tolerance || 
-        usdc_value.checked_sub(base_value).unwrap().abs() <= tolerance,
+        base_value.abs_diff(usdc_value) <= tolerance,
         CrucibleError::InvalidLPAmounts
     );
 
-    // Transfer base token to crucible
-    let cpi_accounts = Transfer {
-        from: ctx.accounts.user_base_token_account.to_account_info(),
-        to: ctx.accounts.crucible_base_vault.to_account_info(),
-        authority: ctx.accounts.user.to_account_info(),
-    };
-    let cpi_program = ctx.accounts.token_program.to_account_info();
-    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-    token::transfer(cpi_ctx, base_amount)?;
-
-    // Transfer USDC to crucible
-    let cpi_accounts = Transfer {
-        from: ctx.accounts.user_usdc_account.to_account_info(),
-        to: ctx.accounts.crucible_usdc_vault.to_account_info(),
-        authority: ctx.accounts.user.to_account_info(),
-    };
-    let cpi_program = ctx.accounts.token_program.to_account_info();
-    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-    token::transfer(cpi_ctx, usdc_amount)?;
-
-    // Create LP position
-    let position_id = crucible.total_lp_positions.checked_add(1).unwrap();
-    let entry_price = base_token_price; // Store entry price
-
-    // Store position (in production, use PDA account)
-    // For now, we'll just increment the counter
-    // The actual position data would be stored in a separate PDA account
-
-    crucible.total_lp_positions = position_id;
-    // Store position (in production, use PDA account)
-    
-    msg!("LP position opened: {} base + {} USDC (position ID: {})", base_amount, usdc_amount, position_id);
+    // Add liquidity to the TOKEN/USDC pool; shares are held by the crucible
+    let base_before = ctx.accounts.user_base_token_account.amount;
+    let usdc_before = ctx.accounts.user_usdc_account.amount;
+    let lp_shares = forge_reactors::cpi::add_liquidity(
+        CpiContext::new(
+            ctx.accounts.reactors_program.to_account_info(),
+            forge_reactors::cpi::accounts::AddLiquidity {
+                pool: ctx.accounts.lp_pool.to_account_info(),
+                user: ctx.accounts.user.to_account_info(),
+                vault_a: ctx.accounts.pool_vault_a.to_account_info(),
+                vault_b: ctx.accounts.pool_vault_b.to_account_info(),
+                lp_mint: ctx.accounts.lp_mint.to_account_info(),
+                user_token_a: ctx.accounts.user_base_token_account.to_account_info(),
+                user_token_b: ctx.accounts.user_usdc_account.to_account_info(),
+                user_lp_account: ctx.accounts.lp_share_vault.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+        ),
+        base_amount,
+        usdc_amount,
+        min_lp_shares,
+    )?
+    .get();
+
+    // The pool may take less than offered to keep its reserve ratio
+    ctx.accounts.user_base_token_account.reload()?;
+    ctx.accounts.user_usdc_account.reload()?;
+    let base_deposited = base_before - ctx.accounts.user_base_token_account.amount;
+    let usdc_deposited = usdc_before - ctx.accounts.user_usdc_account.amount;
+
+    // Create LP position (ID matches the PDA seed)
+    let crucible = &mut ctx.accounts.crucible;
+    let position_id = crucible.next_lp_position_id()?;
+    let deposited_value = oracle::usd_value(base_deposited as u128, base_decimals, &price)?
+        .checked_add(usdc_deposited)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    let position = &mut ctx.accounts.position;
+    position.id = position_id;
+    position.owner = ctx.accounts.user.key();
+    position.crucible = crucible.key();
+    position.base_mint = crucible.base_mint;
+    position.base_amount = base_deposited;
+    position.usdc_amount = usdc_deposited;
+    position.lp_shares = lp_shares;
+    position.entry_price = base_token_price;
+    position.current_value = deposited_value;
+    position.yield_earned = 0;
+    position.is_open = true;
+    position.created_at = clock.unix_timestamp;
+    position.bump = ctx.bumps.position;
+
+    crucible.lp_position_nonce = position_id;
+    crucible.total_lp_positions = crucible.total_lp_positions
+        .checked_add(1)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    msg!("LP position opened: {} base + {} USDC for {} LP shares (position ID: {})", base_deposited, usdc_deposited, lp_shares, position_id);
     Ok(position_id)
 }
 
 pub fn close_lp_position(
     ctx: Context<CloseLPPosition>,
     position_id: u64,
+    min_base_out: u64,
+    min_usdc_out: u64,
 ) -> Result<()> {
-    let crucible = &mut ctx.accounts.crucible;
+    let crucible = &ctx.accounts.crucible;
+    let position = &ctx.accounts.position;
 
-    // In production, fetch position from PDA
-    // For now, validate position exists and is open
-    require!(
-        position_id > 0 && position_id <= crucible.total_lp_positions,
-        CrucibleError::PositionNotFound
-    );
+    require!(position.is_open, CrucibleError::PositionNotOpen);
+    require_active(crucible, ctx.accounts.forge_protocol.as_deref(), PAUSE_WITHDRAWALS)?;
 
-    // Calculate return amounts (with yield)
-    // Simplified: return proportional amounts
-    // In production, calculate based on LP pool reserves
+    // Redeem the position's pool shares straight to the owner at current reserves
+    let seeds = &[
+        b"crucible",
+        crucible.base_mint.as_ref(),
+        &[crucible.bump],
+    ];
+    let signer = &[&seeds[..]];
 
-    // Transfer tokens back to user
-    // (Implementation depends on LP pool mechanics)
+    forge_reactors::cpi::remove_liquidity(
+        CpiContext::new_with_signer(
+            ctx.accounts.reactors_program.to_account_info(),
+            forge_reactors::cpi::accounts::RemoveLiquidity {
+                pool: ctx.accounts.lp_pool.to_account_info(),
+                user: ctx.accounts.crucible_authority.to_account_info(),
+                vault_a: ctx.accounts.pool_vault_a.to_account_info(),
+                vault_b: ctx.accounts.pool_vault_b.to_account_info(),
+                lp_mint: ctx.accounts.lp_mint.to_account_info(),
+                user_token_a: ctx.accounts.user_base_token_account.to_account_info(),
+                user_token_b: ctx.accounts.user_usdc_account.to_account_info(),
+                user_lp_account: ctx.accounts.lp_share_vault.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+            signer,
+        ),
+        position.lp_shares,
+        min_base_out,
+        min_usdc_out,
+    )?;
 
-    // Update crucible state
-    crucible.total_lp_positions = crucible.total_lp_positions
-        .checked_sub(1)
-        .unwrap_or(0);
+    // Update crucible state (position account is closed to the owner)
+    let crucible = &mut ctx.accounts.crucible;
+    crucible.total_lp_positions = crucible.total_lp_positions.saturating_sub(1);
 
     msg!("LP position closed: {}", position_id);
     Ok(())
@@ -119,42 +153,119 @@ pub fn close_lp_position(
 #[derive(Accounts)]
 pub struct OpenLPPosition<'info> {
     #[account(mut)]
-    pub crucible: Account<'info, Crucible>,
+    pub crucible: Box<Account<'info, Crucible>>,
+    #[account(address = crucible.oracle)]
+    pub base_price_feed: Box<Account<'info, PriceFeed>>,
+    #[account(address = crucible.base_mint @ CrucibleError::InvalidBaseMint)]
+    pub base_mint: Box<Account<'info, Mint>>,
     #[account(mut)]
     pub user: Signer<'info>,
+    #[account(
+        init,
+        payer = user,
+        space = LPPosition::LEN,
+        seeds = [
+            b"lp_position",
+            user.key().as_ref(),
+            crucible.key().as_ref(),
+            &crucible.next_lp_position_id()?.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub position: Box<Account<'info, LPPosition>>,
+    #[account(mut, token::mint = crucible.base_mint)]
+    pub user_base_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(mut, token::mint = lp_pool.token_b_mint)]
+    pub user_usdc_account: Box<Account<'info, TokenAccount>>,
+    /// Quote mint of the pool; valued at 1.0 so it must carry USD_DECIMALS
+    #[account(address = lp_pool.token_b_mint, mint::decimals = oracle::USD_DECIMALS as u8)]
+    pub usdc_mint: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        address = crucible.lp_pool,
+        constraint = lp_pool.token_a_mint == crucible.base_mint @ CrucibleError::InvalidBaseMint,
+    )]
+    pub lp_pool: Box<Account<'info, Pool>>,
+    /// CHECK: pool reserve vault, validated by the reactors program
     #[account(mut)]
-    pub user_base_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub user_usdc_account: Account<'info, TokenAccount>,
+    pub pool_vault_a: UncheckedAccount<'info>,
+    /// CHECK: pool reserve vault, validated by the reactors program
     #[account(mut)]
-    pub crucible_base_vault: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub crucible_usdc_vault: Account<'info, TokenAccount>,
+    pub pool_vault_b: UncheckedAccount<'info>,
+    #[account(mut, address = lp_pool.lp_mint)]
+    pub lp_mint: Box<Account<'info, Mint>>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        seeds = [b"lp_shares", crucible.key().as_ref()],
+        bump,
+        token::mint = lp_mint,
+        token::authority = crucible_authority,
+    )]
+    pub lp_share_vault: Box<Account<'info, TokenAccount>>,
+    /// CHECK: Crucible authority PDA
+    #[account(
+        seeds = [b"crucible", crucible.base_mint.as_ref()],
+        bump = crucible.bump,
+    )]
+    pub crucible_authority: UncheckedAccount<'info>,
+    pub reactors_program: Program<'info, ForgeReactors>,
     pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    #[account(seeds = [b"forge_protocol"], bump = forge_protocol.bump, seeds::program = forge_core::ID)]
+    pub forge_protocol: Option<Account<'info, ForgeProtocol>>,
 }
 
 #[derive(Accounts)]
+#[instruction(position_id: u64)]
 pub struct CloseLPPosition<'info> {
     #[account(mut)]
-    pub crucible: Account<'info, Crucible>,
+    pub crucible: Box<Account<'info, Crucible>>,
     #[account(mut)]
     pub user: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"lp_position",
+            user.key().as_ref(),
+            crucible.key().as_ref(),
+            &position_id.to_le_bytes(),
+        ],
+        bump = position.bump,
+        constraint = position.owner == user.key() @ CrucibleError::Unauthorized,
+        constraint = position.crucible == crucible.key() @ CrucibleError::InvalidPosition,
+        close = user,
+    )]
+    pub position: Box<Account<'info, LPPosition>>,
+    #[account(mut, token::mint = crucible.base_mint)]
+    pub user_base_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(mut, token::mint = lp_pool.token_b_mint)]
+    pub user_usdc_account: Box<Account<'info, TokenAccount>>,
+    #[account(mut, address = crucible.lp_pool)]
+    pub lp_pool: Box<Account<'info, Pool>>,
+    /// CHECK: pool reserve vault, validated by the reactors program
     #[account(mut)]
-    pub user_base_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub user_usdc_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub crucible_base_vault: Account<'info, TokenAccount>,
+    pub pool_vault_a: UncheckedAccount<'info>,
+    /// CHECK: pool reserve vault, validated by the reactors program
     #[account(mut)]
-    pub crucible_usdc_vault: Account<'info, TokenAccount>,
+    pub pool_vault_b: UncheckedAccount<'info>,
+    #[account(mut, address = lp_pool.lp_mint)]
+    pub lp_mint: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [b"lp_shares", crucible.key().as_ref()],
+        bump,
+    )]
+    pub lp_share_vault: Box<Account<'info, TokenAccount>>,
+    /// CHECK: Crucible authority PDA
+    #[account(
+        seeds = [b"crucible", crucible.base_mint.as_ref()],
+        bump = crucible.bump,
+    )]
+    pub crucible_authority: UncheckedAccount<'info>,
+    pub reactors_program: Program<'info, ForgeReactors>,
     pub token_program: Program<'info, Token>,
+    #[account(seeds = [b"forge_protocol"], bump = forge_protocol.bump, seeds::program = forge_core::ID)]
+    pub forge_protocol: Option<Account<'info, ForgeProtocol>>,
 }
-
-#[error_code]
-pub enum CrucibleError {
-    #[msg("Invalid LP amounts - must be equal value")]
-    InvalidLPAmounts,
-    #[msg("Position not found")]
-    PositionNotFound,
-}
-
@@ -1,17 +1,31 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Mint, Transfer};
+use anchor_spl::token::{self, Token, TokenAccount, Mint, MintTo, Burn, Transfer};
+use forge_core::ForgeProtocol;
+use forge_reactors::program::ForgeReactors;
+use forge_reactors::state::Pool;
+use forge_math::{bps_of, to_u64, Decimal, Rounding, BPS_SCALE};
+use lending_pool_usdc::program::LendingPoolUsdc;
+use lending_pool_usdc::LendingPool;
+use oracle::state::PriceFeed;
+use crate::crucible::{require_active, require_not_halted};
+use crate::ctoken::{calculate_exchange_rate, fee_split_for, CrucibleError};
 use crate::state::*;
 
-/// Open a leveraged LP position
+/// Open a leveraged position. Borrowed USDC is swapped for base through the crucible's
+/// TOKEN/USDC pool and deposited with the collateral; the position holds cTokens, not
+/// pool LP shares, and the pool is only used for swaps.
 pub fn open_leveraged_position(
     ctx: Context<OpenLeveragedPosition>,
     collateral_amount: u64,
     leverage_factor: u64, // 150 = 1.5x, 200 = 2x (scaled by 100)
+    min_base_out: u64,
 ) -> Result<u64> {
     let position = &mut ctx.accounts.position;
     let crucible = &mut ctx.accounts.crucible;
     let clock = Clock::get()?;
 
+    require_active(crucible, ctx.accounts.forge_protocol.as_deref(), PAUSE_LEVERAGE)?;
+
     require!(
         leverage_factor <= 200, // Max 2x
         CrucibleError::InvalidLeverage
@@ -22,31 +36,106 @@ pub fn open_leveraged_position(
         CrucibleError::InvalidLeverage
     );
 
+    // Get base token price from the crucible's oracle (scaled by 1M)
+    let price = oracle::get_price_default(&ctx.accounts.base_price_feed)?;
+    let base_token_price = price.scaled(6)?;
+
+    // Collateral value in USDC base units (USDC and oracle::USD_DECIMALS are both 6)
+    let collateral_value_usdc = oracle::usd_value(
+        collateral_amount as u128,
+        ctx.accounts.base_token_mint.decimals,
+        &price,
+    )?;
+
     // Calculate borrowed USDC amount
-    // For 2x leverage: borrow = collateral (100% of collateral value)
-    // For 1.5x leverage: borrow = 0.5 * collateral
+    // For 2x leverage: borrow = collateral value (100% of collateral value)
+    // For 1.5x leverage: borrow = 0.5 * collateral value
     let leverage_multiplier = leverage_factor as u128;
-    let borrowed_usdc = collateral_amount as u128
+    let borrowed_usdc = (collateral_value_usdc as u128)
         .checked_mul(leverage_multiplier - 100) // (leverage - 1) * 100
         .and_then(|v| v.checked_div(100))
         .ok_or(ProgramError::ArithmeticOverflow)? as u64;
 
-    // Get base token price (simplified - in production use oracle)
-    let base_token_price = if crucible.base_mint == ctx.accounts.base_token_mint.key() {
-        500_000 // $0.50 scaled by 1M (for FOGO)
-    } else {
-        2_000 // $0.002 scaled by 1M (for FORGE)
-    };
+    // Borrow USDC from the lending pool; the crucible PDA is the whitelisted borrower
+    let seeds = &[
+        b"crucible",
+        crucible.base_mint.as_ref(),
+        &[crucible.bump],
+    ];
+    let signer = &[&seeds[..]];
+    let vault_amount = ctx.accounts.crucible_vault.amount;
+    let mut debt_scaled = 0;
+    let mut base_purchased = 0;
+    if borrowed_usdc > 0 {
+        lending_pool_usdc::cpi::borrow_usdc(
+            CpiContext::new_with_signer(
+                ctx.accounts.lending_pool_program.to_account_info(),
+                lending_pool_usdc::cpi::accounts::BorrowUSDC {
+                    pool: ctx.accounts.lending_pool.to_account_info(),
+                    borrower: ctx.accounts.crucible_authority.to_account_info(),
+                    authorization: ctx.accounts.borrower_authorization.to_account_info(),
+                    payer: ctx.accounts.user.to_account_info(),
+                    borrower_account: ctx.accounts.pool_borrower_account.to_account_info(),
+                    pool_vault: ctx.accounts.pool_vault.to_account_info(),
+                    borrower_usdc_account: ctx.accounts.lvf_usdc_vault.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                },
+                signer,
+            ),
+            borrowed_usdc,
+        )?;
 
-    // Calculate collateral value in USDC (simplified)
-    let collateral_value_usdc = collateral_amount as u128
-        .checked_mul(base_token_price as u128)
-        .and_then(|v| v.checked_div(1_000_000))
-        .ok_or(ProgramError::ArithmeticOverflow)? as u64;
+        // This position's share of the crucible's pool debt, at the post-accrual index
+        ctx.accounts.lending_pool.reload()?;
+        debt_scaled = Decimal::from_scaled(ctx.accounts.lending_pool.borrow_index)
+            .div_amount(borrowed_usdc as u128, Rounding::Up)?;
 
-    // Borrow USDC from lending pool
-    // Note: In production, this would call lending_pool::borrow_usdc
-    // For now, we'll track it in the position
+        // Buy more base with the loan through the crucible's LP pool; the output lands
+        // directly in the crucible vault and becomes part of the position's deposit
+        base_purchased = forge_reactors::cpi::swap(
+            CpiContext::new_with_signer(
+                ctx.accounts.reactors_program.to_account_info(),
+                forge_reactors::cpi::accounts::Swap {
+                    pool: ctx.accounts.lp_pool.to_account_info(),
+                    user: ctx.accounts.crucible_authority.to_account_info(),
+                    vault_a: ctx.accounts.pool_vault_a.to_account_info(),
+                    vault_b: ctx.accounts.pool_vault_b.to_account_info(),
+                    user_token_a: ctx.accounts.crucible_vault.to_account_info(),
+                    user_token_b: ctx.accounts.lvf_usdc_vault.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                },
+                signer,
+            ),
+            borrowed_usdc,
+            min_base_out,
+            false,
+        )?
+        .get();
+    }
+    let base_deposited = collateral_amount
+        .checked_add(base_purchased)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    // Collateral plus purchased base is deposited like any cToken mint, priced at the
+    // vault balance before the swap: the position owns cTokens at the current exchange
+    // rate, so its yield is exactly the vault's real rate growth
+    let entry_exchange_rate = calculate_exchange_rate(
+        crucible,
+        vault_amount,
+        ctx.accounts.ctoken_mint.supply,
+    )?;
+
+    // Deposit fee as on mint_ctoken: the holder share stays in the vault, the treasury
+    // share goes to the fee vault, and shares are minted on the net amount
+    let (holder_fee, treasury_fee) = fee_split_for(crucible, &ctx.accounts.fee_vault, base_deposited)?;
+    let net_deposited = base_deposited - holder_fee - treasury_fee;
+    let ctoken_shares = ctokens_for_deposit(
+        net_deposited,
+        vault_amount,
+        ctx.accounts.ctoken_mint.supply,
+    )?;
+    require!(ctoken_shares > 0, CrucibleError::ZeroCTokens);
 
     // Transfer collateral from user to crucible vault
     let cpi_accounts = Transfer {
@@ -58,21 +147,58 @@ pub fn open_leveraged_position(
     let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
     token::transfer(cpi_ctx, collateral_amount)?;
 
-    // Initialize position
-    position.id = ctx.accounts.position_id.key();
+    if let (Some(fee_vault), true) = (&ctx.accounts.fee_vault, treasury_fee > 0) {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.crucible_vault.to_account_info(),
+            to: fee_vault.to_account_info(),
+            authority: ctx.accounts.crucible_authority.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer), treasury_fee)?;
+    }
+
+    // Position cTokens are held by the crucible until close
+    let cpi_accounts = MintTo {
+        mint: ctx.accounts.ctoken_mint.to_account_info(),
+        to: ctx.accounts.lvf_ctoken_vault.to_account_info(),
+        authority: ctx.accounts.crucible_authority.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    token::mint_to(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer), ctoken_shares)?;
+
+    // Initialize position (ID matches the PDA seed)
+    let lvf_user = &mut ctx.accounts.lvf_user;
+    let position_id = lvf_user.next_position_id()?;
+    lvf_user.owner = ctx.accounts.user.key();
+    lvf_user.crucible = crucible.key();
+    lvf_user.position_nonce = position_id;
+    lvf_user.open_positions = lvf_user.open_positions
+        .checked_add(1)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    lvf_user.bump = ctx.bumps.lvf_user;
+
+    position.id = position_id;
     position.owner = ctx.accounts.user.key();
+    position.crucible = crucible.key();
     position.token = ctx.accounts.base_token_mint.key();
-    position.collateral = collateral_amount;
+    position.collateral = net_deposited;
     position.borrowed_usdc = borrowed_usdc;
+    position.debt_scaled = debt_scaled;
+    position.ctoken_shares = ctoken_shares;
+    position.entry_exchange_rate = entry_exchange_rate;
     position.leverage_factor = leverage_factor;
     position.entry_price = base_token_price;
     position.current_value = collateral_value_usdc;
     position.yield_earned = 0;
     position.is_open = true;
-    position.created_at = clock.slot;
+    position.created_at = clock.unix_timestamp;
+    position.created_slot = clock.slot;
     position.bump = ctx.bumps.position;
 
     // Update crucible state
+    crucible.total_base_deposited = crucible.total_base_deposited
+        .checked_add(base_deposited - treasury_fee)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
     crucible.total_leveraged_positions = crucible.total_leveraged_positions
         .checked_add(1)
         .ok_or(ProgramError::ArithmeticOverflow)?;
@@ -82,52 +208,31 @@ pub fn open_leveraged_position(
         owner: position.owner,
         token: position.token,
         collateral: collateral_amount,
+        ctoken_shares,
         borrowed_usdc,
+        base_purchased,
         leverage_factor,
+        holder_fee,
+        treasury_fee,
     });
 
-    Ok(position.id.key())
+    Ok(position_id)
 }
 
-/// Close a leveraged LP position
+/// Close a leveraged position, redeeming its cTokens and selling base to repay the loan
 pub fn close_leveraged_position(
     ctx: Context<CloseLeveragedPosition>,
-    position_id: Pubkey,
+    position_id: u64,
+    min_base_out: u64,
 ) -> Result<()> {
     let position = &mut ctx.accounts.position;
     let crucible = &mut ctx.accounts.crucible;
-    let clock = Clock::get()?;
 
+    require!(position.id == position_id, CrucibleError::InvalidPosition);
     require!(position.is_open, CrucibleError::PositionNotOpen);
     require!(position.owner == ctx.accounts.user.key(), CrucibleError::Unauthorized);
+    require_active(crucible, ctx.accounts.forge_protocol.as_deref(), PAUSE_WITHDRAWALS)?;
 
-    // Calculate yield earned (simplified - in production use exchange rate growth)
-    // Yield increases cToken exchange rate
-    let base_token_price = position.entry_price;
-    let current_exchange_rate = calculate_lvf_exchange_rate(
-        crucible,
-        position.collateral,
-        position.borrowed_usdc,
-        clock.slot - position.created_at,
-    )?;
-
-    // Calculate tokens to return (includes yield)
-    let tokens_to_return = position.collateral as u128
-        .checked_mul(current_exchange_rate)
-        .and_then(|v| v.checked_div(1_000_000))
-        .ok_or(ProgramError::ArithmeticOverflow)? as u64;
-
-    // Calculate protocol fee (0.3%)
-    let protocol_fee = tokens_to_return as u128
-        .checked_mul(3)
-        .and_then(|v| v.checked_div(1000))
-        .ok_or(ProgramError::ArithmeticOverflow)? as u64;
-
-    let tokens_after_fee = tokens_to_return
-        .checked_sub(protocol_fee)
-        .ok_or(ProgramError::ArithmeticOverflow)?;
-
-    // Transfer tokens back to user (minus fees)
     let seeds = &[
         b"crucible",
         crucible.base_mint.as_ref(),
@@ -135,6 +240,112 @@ pub fn close_leveraged_position(
     ];
     let signer = &[&seeds[..]];
 
+    // Redeem the position's cTokens at the current vault exchange rate; the payout is
+    // bounded by the shares the position holds, never by a modelled yield
+    let base_out = base_for_ctokens(
+        position.ctoken_shares,
+        ctx.accounts.crucible_vault.amount,
+        ctx.accounts.ctoken_mint.supply,
+    )?;
+    require!(base_out <= ctx.accounts.crucible_vault.amount, CrucibleError::InsufficientLiquidity);
+
+    // Withdrawal fee as on burn_ctoken: the holder share stays in the vault, the
+    // treasury share goes to the fee vault
+    let (holder_fee, treasury_fee) = fee_split_for(crucible, &ctx.accounts.fee_vault, base_out)?;
+    let tokens_after_fee = base_out - holder_fee - treasury_fee;
+
+    let cpi_accounts = Burn {
+        mint: ctx.accounts.ctoken_mint.to_account_info(),
+        from: ctx.accounts.lvf_ctoken_vault.to_account_info(),
+        authority: ctx.accounts.crucible_authority.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    token::burn(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer), position.ctoken_shares)?;
+
+    // Sell just enough of the redeemed base to repay the loan plus accrued interest
+    let mut usdc_repaid = 0;
+    let mut usdc_refund = 0;
+    let mut base_sold = 0;
+    if position.debt_scaled > 0 {
+        lending_pool_usdc::cpi::accrue_interest(CpiContext::new(
+            ctx.accounts.lending_pool_program.to_account_info(),
+            lending_pool_usdc::cpi::accounts::AccrueInterest {
+                pool: ctx.accounts.lending_pool.to_account_info(),
+            },
+        ))?;
+        ctx.accounts.lending_pool.reload()?;
+        let owed = to_u64(
+            Decimal::from_scaled(ctx.accounts.lending_pool.borrow_index)
+                .mul_amount(position.debt_scaled, Rounding::Up)?,
+        )?;
+
+        base_sold = forge_reactors::swap_input_for_output(
+            owed,
+            ctx.accounts.pool_vault_a.amount,
+            ctx.accounts.pool_vault_b.amount,
+            ctx.accounts.lp_pool.fee_bps,
+        )?;
+        require!(base_sold <= tokens_after_fee, CrucibleError::PositionUnderwater);
+
+        let usdc_out = forge_reactors::cpi::swap(
+            CpiContext::new_with_signer(
+                ctx.accounts.reactors_program.to_account_info(),
+                forge_reactors::cpi::accounts::Swap {
+                    pool: ctx.accounts.lp_pool.to_account_info(),
+                    user: ctx.accounts.crucible_authority.to_account_info(),
+                    vault_a: ctx.accounts.pool_vault_a.to_account_info(),
+                    vault_b: ctx.accounts.pool_vault_b.to_account_info(),
+                    user_token_a: ctx.accounts.crucible_vault.to_account_info(),
+                    user_token_b: ctx.accounts.lvf_usdc_vault.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                },
+                signer,
+            ),
+            base_sold,
+            owed,
+            true,
+        )?
+        .get();
+
+        // The pool caps the repayment at the crucible's debt under its own rounding, so
+        // the amount actually repaid is read back from the LVF USDC vault
+        ctx.accounts.lvf_usdc_vault.reload()?;
+        let usdc_before = ctx.accounts.lvf_usdc_vault.amount;
+        lending_pool_usdc::cpi::repay_usdc(
+            CpiContext::new_with_signer(
+                ctx.accounts.lending_pool_program.to_account_info(),
+                lending_pool_usdc::cpi::accounts::RepayUSDC {
+                    pool: ctx.accounts.lending_pool.to_account_info(),
+                    borrower: ctx.accounts.crucible_authority.to_account_info(),
+                    borrower_account: ctx.accounts.pool_borrower_account.to_account_info(),
+                    borrower_usdc_account: ctx.accounts.lvf_usdc_vault.to_account_info(),
+                    pool_vault: ctx.accounts.pool_vault.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                },
+                signer,
+            ),
+            owed,
+        )?;
+        ctx.accounts.lvf_usdc_vault.reload()?;
+        usdc_repaid = usdc_before - ctx.accounts.lvf_usdc_vault.amount;
+        position.debt_scaled = 0;
+
+        // Rounding up the swap input can buy slightly more than the pool took
+        usdc_refund = usdc_out - usdc_repaid;
+        if usdc_refund > 0 {
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.lvf_usdc_vault.to_account_info(),
+                to: ctx.accounts.user_usdc_account.to_account_info(),
+                authority: ctx.accounts.crucible_authority.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer), usdc_refund)?;
+        }
+    }
+
+    // Transfer the remaining base back to user (minus fees and the base sold)
+    let collateral_returned = tokens_after_fee - base_sold;
+    require!(collateral_returned >= min_base_out, CrucibleError::SlippageExceeded);
     let cpi_accounts = Transfer {
         from: ctx.accounts.crucible_vault.to_account_info(),
         to: ctx.accounts.user_token_account.to_account_info(),
@@ -142,75 +353,294 @@ pub fn close_leveraged_position(
     };
     let cpi_program = ctx.accounts.token_program.to_account_info();
     let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
-    token::transfer(cpi_ctx, tokens_after_fee)?;
+    token::transfer(cpi_ctx, collateral_returned)?;
+
+    if let (Some(fee_vault), true) = (&ctx.accounts.fee_vault, treasury_fee > 0) {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.crucible_vault.to_account_info(),
+            to: fee_vault.to_account_info(),
+            authority: ctx.accounts.crucible_authority.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer), treasury_fee)?;
+    }
+
+    // Realized value in USDC: returned collateral at the current price plus any USDC
+    // refund; the loan and its interest were paid out of the redeemed base, so PnL is
+    // negative when price moves and borrowing cost exceed yield
+    let price = oracle::get_price_default(&ctx.accounts.base_price_feed)?;
+    let exit_value = oracle::usd_value(collateral_returned as u128, ctx.accounts.base_token_mint.decimals, &price)?;
+    let net_value = exit_value as i128 + usdc_refund as i128;
+    let pnl_usdc = i64::try_from(net_value - position.current_value as i128)
+        .map_err(|_| ProgramError::ArithmeticOverflow)?;
 
     // Update position
     position.is_open = false;
-    position.yield_earned = tokens_to_return
-        .checked_sub(position.collateral)
-        .unwrap_or(0);
+    position.yield_earned = tokens_after_fee.saturating_sub(position.collateral);
+    position.current_value = net_value.max(0) as u64;
 
-    // Update crucible state
+    let withdrawn = tokens_after_fee + treasury_fee;
+    crucible.total_base_deposited = crucible.total_base_deposited.saturating_sub(withdrawn);
+
+    // Update crucible and owner state (position account is closed to the owner)
     crucible.total_leveraged_positions = crucible.total_leveraged_positions
-        .checked_sub(1)
-        .ok_or(0);
+        .saturating_sub(1);
+    let lvf_user = &mut ctx.accounts.lvf_user;
+    lvf_user.open_positions = lvf_user.open_positions.saturating_sub(1);
 
     emit!(LeveragedPositionClosed {
         position_id: position.id,
         owner: position.owner,
-        collateral_returned: tokens_after_fee,
+        collateral_returned,
+        usdc_repaid,
+        base_sold,
         yield_earned: position.yield_earned,
+        pnl_usdc,
     });
 
     Ok(())
 }
 
-/// Calculate LVF exchange rate based on time and leverage
-fn calculate_lvf_exchange_rate(
-    crucible: &Crucible,
-    collateral: u64,
-    borrowed_usdc: u64,
-    slots_elapsed: u64,
-) -> Result<u64> {
-    // Base exchange rate starts at 1.0 (1_000_000 scaled)
-    let base_rate = 1_000_000u64;
-
-    // Calculate effective APY with leverage
-    // Effective APY = Base APY * Leverage - Borrow Cost
-    let leverage_multiplier = (borrowed_usdc as u128 * 100) / collateral as u128 + 100;
-    let base_apy = crucible.fee_rate; // Use crucible fee rate as base APY
-    let borrow_rate = 10_000_000; // 10% (scaled by 100M)
-    
-    let effective_apy = (base_apy as u128 * leverage_multiplier) / 100
-        .checked_sub((borrow_rate * (leverage_multiplier - 100)) / 100)
-        .unwrap_or(0);
-
-    // Convert slots to years (assuming 400ms per slot)
-    let slots_per_year = 365 * 24 * 60 * 60 * 1000 / 400; // ~78.8M slots
-    let years_elapsed = slots_elapsed as u128 * 1_000_000 / slots_per_year;
-
-    // Calculate exchange rate growth: rate = (1 + APY)^years
-    // Simplified: rate = 1 + (APY * years)
-    let growth = (base_rate as u128 * effective_apy * years_elapsed) / (100 * 1_000_000 * 1_000_000);
-    let exchange_rate = base_rate as u128 + growth;
-
-    Ok(exchange_rate.min(u64::MAX as u128) as u64)
+/// Liquidate a leveraged position whose debt exceeds the crucible's
+/// `lvf_liquidation_threshold_bps` of its redeemable value (permissionless). The
+/// position's cTokens are redeemed and base is sold to repay the loan first; debt the
+/// base cannot cover is written off in the lending pool so it stops accruing on the
+/// crucible's borrower account. Out of any base left over, the liquidator takes
+/// `lvf_liquidation_bonus_bps` of the base sold and the rest goes to the owner.
+/// The sale must fetch at least the oracle value of the base less
+/// `lvf_max_liquidation_slippage_bps`, and at least `min_usdc_out`.
+pub fn liquidate_leveraged_position(
+    ctx: Context<LiquidateLeveragedPosition>,
+    position_id: u64,
+    min_usdc_out: u64,
+) -> Result<()> {
+    let position = &mut ctx.accounts.position;
+    let crucible = &mut ctx.accounts.crucible;
+
+    require!(position.id == position_id, CrucibleError::InvalidPosition);
+    require!(position.is_open, CrucibleError::PositionNotOpen);
+    require!(position.debt_scaled > 0, CrucibleError::PositionHealthy);
+    require_not_halted(crucible, ctx.accounts.forge_protocol.as_deref())?;
+
+    let seeds = &[
+        b"crucible",
+        crucible.base_mint.as_ref(),
+        &[crucible.bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    lending_pool_usdc::cpi::accrue_interest(CpiContext::new(
+        ctx.accounts.lending_pool_program.to_account_info(),
+        lending_pool_usdc::cpi::accounts::AccrueInterest {
+            pool: ctx.accounts.lending_pool.to_account_info(),
+        },
+    ))?;
+    ctx.accounts.lending_pool.reload()?;
+    let owed = to_u64(
+        Decimal::from_scaled(ctx.accounts.lending_pool.borrow_index)
+            .mul_amount(position.debt_scaled, Rounding::Up)?,
+    )?;
+
+    // Value the position as close_leveraged_position would redeem it
+    let base_out = base_for_ctokens(
+        position.ctoken_shares,
+        ctx.accounts.crucible_vault.amount,
+        ctx.accounts.ctoken_mint.supply,
+    )?;
+    require!(base_out <= ctx.accounts.crucible_vault.amount, CrucibleError::InsufficientLiquidity);
+    let (holder_fee, treasury_fee) = fee_split_for(crucible, &ctx.accounts.fee_vault, base_out)?;
+    let tokens_after_fee = base_out - holder_fee - treasury_fee;
+
+    let price = oracle::get_price_default(&ctx.accounts.base_price_feed)?;
+    let position_value = oracle::usd_value(tokens_after_fee as u128, ctx.accounts.base_token_mint.decimals, &price)?;
+    require!(
+        owed as u128 * BPS_SCALE > position_value as u128 * crucible.lvf_liquidation_threshold_bps as u128,
+        CrucibleError::PositionHealthy
+    );
+
+    let cpi_accounts = Burn {
+        mint: ctx.accounts.ctoken_mint.to_account_info(),
+        from: ctx.accounts.lvf_ctoken_vault.to_account_info(),
+        authority: ctx.accounts.crucible_authority.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    token::burn(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer), position.ctoken_shares)?;
+
+    // Sell just enough base to repay the loan, or all of it when that falls short
+    let reserve_a = ctx.accounts.pool_vault_a.amount;
+    let reserve_b = ctx.accounts.pool_vault_b.amount;
+    let fee_bps = ctx.accounts.lp_pool.fee_bps;
+    let mut base_sold = match forge_reactors::swap_input_for_output(owed, reserve_a, reserve_b, fee_bps) {
+        Ok(needed) if needed <= tokens_after_fee => needed,
+        _ => tokens_after_fee,
+    };
+    let quoted_out = if base_sold > 0 {
+        forge_reactors::swap_output(base_sold, reserve_a, reserve_b, fee_bps)?
+    } else {
+        0
+    };
+    if quoted_out == 0 {
+        // Too little base to buy any USDC; nothing is sold and the debt is written off
+        base_sold = 0;
+    }
+
+    // Pool reserves can be pushed around within the transaction, so the sale is bounded
+    // by the oracle rather than the pool's own quote. When the base covers the loan the
+    // sale must also repay it in full, otherwise surplus base would be misread as bad debt.
+    let oracle_floor = to_u64(bps_of(
+        oracle::usd_value(base_sold as u128, ctx.accounts.base_token_mint.decimals, &price)? as u128,
+        BPS_SCALE as u64 - crucible.lvf_max_liquidation_slippage_bps,
+        Rounding::Up,
+    )?)?;
+    let mut min_swap_out = oracle_floor.max(min_usdc_out);
+    if base_sold < tokens_after_fee {
+        min_swap_out = min_swap_out.max(owed);
+    }
+
+    let mut usdc_out = 0;
+    let mut usdc_repaid = 0;
+    if quoted_out > 0 {
+        usdc_out = forge_reactors::cpi::swap(
+            CpiContext::new_with_signer(
+                ctx.accounts.reactors_program.to_account_info(),
+                forge_reactors::cpi::accounts::Swap {
+                    pool: ctx.accounts.lp_pool.to_account_info(),
+                    user: ctx.accounts.crucible_authority.to_account_info(),
+                    vault_a: ctx.accounts.pool_vault_a.to_account_info(),
+                    vault_b: ctx.accounts.pool_vault_b.to_account_info(),
+                    user_token_a: ctx.accounts.crucible_vault.to_account_info(),
+                    user_token_b: ctx.accounts.lvf_usdc_vault.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                },
+                signer,
+            ),
+            base_sold,
+            min_swap_out,
+            true,
+        )?
+        .get();
+
+        ctx.accounts.lvf_usdc_vault.reload()?;
+        let usdc_before = ctx.accounts.lvf_usdc_vault.amount;
+        lending_pool_usdc::cpi::repay_usdc(
+            CpiContext::new_with_signer(
+                ctx.accounts.lending_pool_program.to_account_info(),
+                lending_pool_usdc::cpi::accounts::RepayUSDC {
+                    pool: ctx.accounts.lending_pool.to_account_info(),
+                    borrower: ctx.accounts.crucible_authority.to_account_info(),
+                    borrower_account: ctx.accounts.pool_borrower_account.to_account_info(),
+                    borrower_usdc_account: ctx.accounts.lvf_usdc_vault.to_account_info(),
+                    pool_vault: ctx.accounts.pool_vault.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                },
+                signer,
+            ),
+            usdc_out.min(owed),
+        )?;
+        ctx.accounts.lvf_usdc_vault.reload()?;
+        usdc_repaid = usdc_before - ctx.accounts.lvf_usdc_vault.amount;
+    }
+
+    // All base was sold, the pool took all of it and it still fell short:
+    // the remainder is bad debt
+    let bad_debt = if usdc_out < owed && usdc_repaid == usdc_out { owed - usdc_repaid } else { 0 };
+    if bad_debt > 0 {
+        lending_pool_usdc::cpi::write_off_bad_debt(
+            CpiContext::new_with_signer(
+                ctx.accounts.lending_pool_program.to_account_info(),
+                lending_pool_usdc::cpi::accounts::WriteOffBadDebt {
+                    pool: ctx.accounts.lending_pool.to_account_info(),
+                    borrower: ctx.accounts.crucible_authority.to_account_info(),
+                    authorization: ctx.accounts.borrower_authorization.to_account_info(),
+                    borrower_account: ctx.accounts.pool_borrower_account.to_account_info(),
+                },
+                signer,
+            ),
+            bad_debt,
+        )?;
+    }
+    position.debt_scaled = 0;
+
+    let usdc_refund = usdc_out - usdc_repaid;
+    if usdc_refund > 0 {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.lvf_usdc_vault.to_account_info(),
+            to: ctx.accounts.owner_usdc_account.to_account_info(),
+            authority: ctx.accounts.crucible_authority.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer), usdc_refund)?;
+    }
+
+    // The debt is settled; the liquidator's bonus only comes out of base left over
+    let surplus = tokens_after_fee - base_sold;
+    let liquidator_bonus = to_u64(bps_of(base_sold as u128, crucible.lvf_liquidation_bonus_bps, Rounding::Down)?)?
+        .min(surplus);
+    let collateral_returned = surplus - liquidator_bonus;
+    for (to, amount) in [
+        (ctx.accounts.owner_token_account.to_account_info(), collateral_returned),
+        (ctx.accounts.liquidator_token_account.to_account_info(), liquidator_bonus),
+    ] {
+        if amount > 0 {
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.crucible_vault.to_account_info(),
+                to,
+                authority: ctx.accounts.crucible_authority.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer), amount)?;
+        }
+    }
+
+    if let (Some(fee_vault), true) = (&ctx.accounts.fee_vault, treasury_fee > 0) {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.crucible_vault.to_account_info(),
+            to: fee_vault.to_account_info(),
+            authority: ctx.accounts.crucible_authority.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer), treasury_fee)?;
+    }
+
+    position.is_open = false;
+    crucible.total_base_deposited = crucible.total_base_deposited.saturating_sub(tokens_after_fee + treasury_fee);
+    crucible.total_leveraged_positions = crucible.total_leveraged_positions.saturating_sub(1);
+    let lvf_user = &mut ctx.accounts.lvf_user;
+    lvf_user.open_positions = lvf_user.open_positions.saturating_sub(1);
+
+    emit!(LeveragedPositionLiquidated {
+        position_id: position.id,
+        owner: position.owner,
+        liquidator: ctx.accounts.liquidator.key(),
+        usdc_repaid,
+        bad_debt,
+        base_sold,
+        liquidator_bonus,
+        collateral_returned,
+    });
+
+    Ok(())
 }
 
 #[derive(Accounts)]
-#[instruction(position_id: Pubkey)]
 pub struct OpenLeveragedPosition<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
 
     #[account(
         mut,
-        has_one = base_mint @ CrucibleError::InvalidBaseMint,
+        constraint = crucible.base_mint == base_token_mint.key() @ CrucibleError::InvalidBaseMint,
     )]
     pub crucible: Account<'info, Crucible>,
 
     pub base_token_mint: Account<'info, Mint>,
 
+    #[account(mut, address = crucible.ctoken_mint)]
+    pub ctoken_mint: Box<Account<'info, Mint>>,
+
+    #[account(address = crucible.oracle)]
+    pub base_price_feed: Account<'info, PriceFeed>,
+
     #[account(mut)]
     pub user_token_account: Account<'info, TokenAccount>,
 
@@ -222,20 +652,27 @@ pub struct OpenLeveragedPosition<'info> {
     pub crucible_vault: Account<'info, TokenAccount>,
 
     #[account(
-        init,
+        init_if_needed,
         payer = user,
-        space = 8 + LeveragedPosition::LEN,
-        seeds = [b"position", user.key().as_ref(), crucible.key().as_ref()],
+        space = 8 + LvfUserState::LEN,
+        seeds = [b"lvf_user", user.key().as_ref(), crucible.key().as_ref()],
         bump,
     )]
-    pub position: Account<'info, LeveragedPosition>,
+    pub lvf_user: Box<Account<'info, LvfUserState>>,
 
-    /// CHECK: Position ID PDA
     #[account(
-        seeds = [b"position", user.key().as_ref(), crucible.key().as_ref()],
+        init,
+        payer = user,
+        space = 8 + LeveragedPosition::LEN,
+        seeds = [
+            b"position",
+            user.key().as_ref(),
+            crucible.key().as_ref(),
+            &lvf_user.next_position_id()?.to_le_bytes(),
+        ],
         bump,
     )]
-    pub position_id: UncheckedAccount<'info>,
+    pub position: Box<Account<'info, LeveragedPosition>>,
 
     /// CHECK: Crucible authority PDA
     #[account(
@@ -244,29 +681,115 @@ pub struct OpenLeveragedPosition<'info> {
     )]
     pub crucible_authority: UncheckedAccount<'info>,
 
+    #[account(mut, seeds = [b"pool"], bump = lending_pool.bump, seeds::program = lending_pool_usdc::ID)]
+    pub lending_pool: Box<Account<'info, LendingPool>>,
+
+    #[account(address = lending_pool.usdc_mint)]
+    pub usdc_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: crucible's borrower whitelist entry, validated by the lending pool
+    pub borrower_authorization: UncheckedAccount<'info>,
+
+    /// CHECK: crucible's debt record, validated (and created on first borrow) by the lending pool
+    #[account(mut)]
+    pub pool_borrower_account: UncheckedAccount<'info>,
+
+    /// CHECK: lending pool USDC vault, validated by the lending pool
+    #[account(mut)]
+    pub pool_vault: UncheckedAccount<'info>,
+
+    /// cTokens owned by open leveraged positions
+    #[account(
+        init_if_needed,
+        payer = user,
+        seeds = [b"lvf_ctokens", crucible.key().as_ref()],
+        bump,
+        token::mint = ctoken_mint,
+        token::authority = crucible_authority,
+    )]
+    pub lvf_ctoken_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Borrowed USDC held for open leveraged positions
+    #[account(
+        init_if_needed,
+        payer = user,
+        seeds = [b"lvf_usdc", crucible.key().as_ref()],
+        bump,
+        token::mint = usdc_mint,
+        token::authority = crucible_authority,
+    )]
+    pub lvf_usdc_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Crucible's TOKEN/USDC pool, used to buy base with the borrowed USDC
+    #[account(
+        address = crucible.lp_pool,
+        constraint = lp_pool.token_a_mint == crucible.base_mint @ CrucibleError::InvalidBaseMint,
+    )]
+    pub lp_pool: Box<Account<'info, Pool>>,
+    /// CHECK: pool reserve vault, validated by the reactors program
+    #[account(mut)]
+    pub pool_vault_a: UncheckedAccount<'info>,
+    /// CHECK: pool reserve vault, validated by the reactors program
+    #[account(mut)]
+    pub pool_vault_b: UncheckedAccount<'info>,
+
+    pub lending_pool_program: Program<'info, LendingPoolUsdc>,
+    pub reactors_program: Program<'info, ForgeReactors>,
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
+
+    #[account(seeds = [b"forge_protocol"], bump = forge_protocol.bump, seeds::program = forge_core::ID)]
+    pub forge_protocol: Option<Account<'info, ForgeProtocol>>,
+
+    #[account(mut, address = crucible.fee_vault)]
+    pub fee_vault: Option<Account<'info, TokenAccount>>,
 }
 
 #[derive(Accounts)]
-#[instruction(position_id: Pubkey)]
+#[instruction(position_id: u64)]
 pub struct CloseLeveragedPosition<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
 
+    #[account(mut)]
+    pub crucible: Account<'info, Crucible>,
+
     #[account(
         mut,
-        has_one = base_mint @ CrucibleError::InvalidBaseMint,
+        seeds = [b"lvf_user", user.key().as_ref(), crucible.key().as_ref()],
+        bump = lvf_user.bump,
     )]
-    pub crucible: Account<'info, Crucible>,
+    pub lvf_user: Box<Account<'info, LvfUserState>>,
 
     #[account(
         mut,
-        seeds = [b"position", user.key().as_ref(), crucible.key().as_ref()],
+        seeds = [
+            b"position",
+            user.key().as_ref(),
+            crucible.key().as_ref(),
+            &position_id.to_le_bytes(),
+        ],
         bump = position.bump,
-        constraint = position.id == position_id @ CrucibleError::InvalidPosition,
+        constraint = position.crucible == crucible.key() @ CrucibleError::InvalidPosition,
+        close = user,
+    )]
+    pub position: Box<Account<'info, LeveragedPosition>>,
+
+    #[account(address = crucible.base_mint @ CrucibleError::InvalidBaseMint)]
+    pub base_token_mint: Box<Account<'info, Mint>>,
+
+    #[account(address = crucible.oracle)]
+    pub base_price_feed: Box<Account<'info, PriceFeed>>,
+
+    #[account(mut, address = crucible.ctoken_mint)]
+    pub ctoken_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [b"lvf_ctokens", crucible.key().as_ref()],
+        bump,
     )]
-    pub position: Account<'info, LeveragedPosition>,
+    pub lvf_ctoken_vault: Box<Account<'info, TokenAccount>>,
 
     #[account(mut)]
     pub user_token_account: Account<'info, TokenAccount>,
@@ -285,44 +808,249 @@ pub struct CloseLeveragedPosition<'info> {
     )]
     pub crucible_authority: UncheckedAccount<'info>,
 
+    #[account(mut, seeds = [b"pool"], bump = lending_pool.bump, seeds::program = lending_pool_usdc::ID)]
+    pub lending_pool: Box<Account<'info, LendingPool>>,
+
+    /// CHECK: crucible's debt record, validated by the lending pool
+    #[account(mut)]
+    pub pool_borrower_account: UncheckedAccount<'info>,
+
+    /// CHECK: lending pool USDC vault, validated by the lending pool
+    #[account(mut)]
+    pub pool_vault: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"lvf_usdc", crucible.key().as_ref()],
+        bump,
+    )]
+    pub lvf_usdc_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Receives any USDC left over after the loan is repaid
+    #[account(mut, token::mint = lvf_usdc_vault.mint)]
+    pub user_usdc_account: Box<Account<'info, TokenAccount>>,
+
+    /// Crucible's TOKEN/USDC pool, used to sell base for the loan repayment
+    #[account(
+        address = crucible.lp_pool,
+        constraint = lp_pool.token_a_mint == crucible.base_mint @ CrucibleError::InvalidBaseMint,
+    )]
+    pub lp_pool: Box<Account<'info, Pool>>,
+    #[account(mut, address = lp_pool.vault_a)]
+    pub pool_vault_a: Box<Account<'info, TokenAccount>>,
+    #[account(mut, address = lp_pool.vault_b)]
+    pub pool_vault_b: Box<Account<'info, TokenAccount>>,
+
+    pub lending_pool_program: Program<'info, LendingPoolUsdc>,
+    pub reactors_program: Program<'info, ForgeReactors>,
     pub token_program: Program<'info, Token>,
+
+    #[account(seeds = [b"forge_protocol"], bump = forge_protocol.bump, seeds::program = forge_core::ID)]
+    pub forge_protocol: Option<Account<'info, ForgeProtocol>>,
+
+    #[account(mut, address = crucible.fee_vault)]
+    pub fee_vault: Option<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+#[instruction(position_id: u64)]
+pub struct LiquidateLeveragedPosition<'info> {
+    pub liquidator: Signer<'info>,
+
+    #[account(mut)]
+    pub crucible: Account<'info, Crucible>,
+
+    /// CHECK: position owner; receives leftovers and the position's rent
+    #[account(mut, address = position.owner @ CrucibleError::Unauthorized)]
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"lvf_user", owner.key().as_ref(), crucible.key().as_ref()],
+        bump = lvf_user.bump,
+    )]
+    pub lvf_user: Box<Account<'info, LvfUserState>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"position",
+            position.owner.as_ref(),
+            crucible.key().as_ref(),
+            &position_id.to_le_bytes(),
+        ],
+        bump = position.bump,
+        constraint = position.crucible == crucible.key() @ CrucibleError::InvalidPosition,
+        close = owner,
+    )]
+    pub position: Box<Account<'info, LeveragedPosition>>,
+
+    #[account(address = crucible.base_mint @ CrucibleError::InvalidBaseMint)]
+    pub base_token_mint: Box<Account<'info, Mint>>,
+
+    #[account(address = crucible.oracle)]
+    pub base_price_feed: Box<Account<'info, PriceFeed>>,
+
+    #[account(mut, address = crucible.ctoken_mint)]
+    pub ctoken_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [b"lvf_ctokens", crucible.key().as_ref()],
+        bump,
+    )]
+    pub lvf_ctoken_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Receives the base left after the repayment and bonus
+    #[account(mut, token::mint = crucible.base_mint, token::authority = owner)]
+    pub owner_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Receives any USDC left over after the loan is repaid
+    #[account(mut, token::mint = lvf_usdc_vault.mint, token::authority = owner)]
+    pub owner_usdc_account: Box<Account<'info, TokenAccount>>,
+
+    /// Receives the liquidation bonus
+    #[account(mut, token::mint = crucible.base_mint)]
+    pub liquidator_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", crucible.key().as_ref()],
+        bump = crucible.vault_bump,
+    )]
+    pub crucible_vault: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Crucible authority PDA
+    #[account(
+        seeds = [b"crucible", crucible.base_mint.as_ref()],
+        bump = crucible.bump,
+    )]
+    pub crucible_authority: UncheckedAccount<'info>,
+
+    #[account(mut, seeds = [b"pool"], bump = lending_pool.bump, seeds::program = lending_pool_usdc::ID)]
+    pub lending_pool: Box<Account<'info, LendingPool>>,
+
+    /// CHECK: crucible's borrower whitelist entry, validated by the lending pool
+    #[account(mut)]
+    pub borrower_authorization: UncheckedAccount<'info>,
+
+    /// CHECK: crucible's debt record, validated by the lending pool
+    #[account(mut)]
+    pub pool_borrower_account: UncheckedAccount<'info>,
+
+    /// CHECK: lending pool USDC vault, validated by the lending pool
+    #[account(mut)]
+    pub pool_vault: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"lvf_usdc", crucible.key().as_ref()],
+        bump,
+    )]
+    pub lvf_usdc_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Crucible's TOKEN/USDC pool, used to sell base for the loan repayment
+    #[account(
+        address = crucible.lp_pool,
+        constraint = lp_pool.token_a_mint == crucible.base_mint @ CrucibleError::InvalidBaseMint,
+    )]
+    pub lp_pool: Box<Account<'info, Pool>>,
+    #[account(mut, address = lp_pool.vault_a)]
+    pub pool_vault_a: Box<Account<'info, TokenAccount>>,
+    #[account(mut, address = lp_pool.vault_b)]
+    pub pool_vault_b: Box<Account<'info, TokenAccount>>,
+
+    pub lending_pool_program: Program<'info, LendingPoolUsdc>,
+    pub reactors_program: Program<'info, ForgeReactors>,
+    pub token_program: Program<'info, Token>,
+
+    #[account(seeds = [b"forge_protocol"], bump = forge_protocol.bump, seeds::program = forge_core::ID)]
+    pub forge_protocol: Option<Account<'info, ForgeProtocol>>,
+
+    #[account(mut, address = crucible.fee_vault)]
+    pub fee_vault: Option<Account<'info, TokenAccount>>,
+}
+
+/// Per-owner LVF bookkeeping for one crucible. Open positions live at
+/// `[b"position", owner, crucible, id]` for ids in `1..=position_nonce`;
+/// closed ones no longer exist, so clients can enumerate by deriving each PDA.
+#[account]
+pub struct LvfUserState {
+    pub owner: Pubkey,
+    pub crucible: Pubkey,
+    pub position_nonce: u64, // Last position ID issued (never reused)
+    pub open_positions: u64,
+    pub bump: u8,
+}
+
+impl LvfUserState {
+    pub const LEN: usize = 32 + 32 + 8 + 8 + 1;
+
+    /// ID (and PDA seed) for the owner's next position
+    pub fn next_position_id(&self) -> Result<u64> {
+        Ok(self.position_nonce.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?)
+    }
 }
 
 #[account]
 pub struct LeveragedPosition {
-    pub id: Pubkey,
+    pub id: u64,
     pub owner: Pubkey,
+    pub crucible: Pubkey,
     pub token: Pubkey, // Base token mint (FOGO or FORGE)
-    pub collateral: u64, // Base token amount deposited
+    pub collateral: u64, // Base deposited net of the deposit fee: user collateral plus base bought with the loan
     pub borrowed_usdc: u64, // USDC borrowed
+    pub debt_scaled: u128, // Share of the crucible's lending pool debt (borrowed / borrow_index)
+    pub ctoken_shares: u64, // cTokens minted for the collateral, held in the LVF cToken vault
+    pub entry_exchange_rate: u64, // cToken exchange rate at open (forge_math::SCALE)
     pub leverage_factor: u64, // 150 = 1.5x, 200 = 2x (scaled by 100)
     pub entry_price: u64, // Entry price in USDC (scaled)
-    pub current_value: u64, // Current position value in USDC
+    pub current_value: u64, // Collateral value in USDC at open; net realized value once closed
     pub yield_earned: u64, // Yield earned in base token
     pub is_open: bool,
-    pub created_at: u64, // Slot when created
+    pub created_at: i64, // Unix timestamp when opened
+    pub created_slot: u64, // Slot when created, for ordering only
     pub bump: u8,
 }
 
 impl LeveragedPosition {
-    pub const LEN: usize = 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 1;
+    pub const LEN: usize = 8 + 32 + 32 + 32 + 8 + 8 + 16 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 8 + 1;
 }
 
 #[event]
 pub struct LeveragedPositionOpened {
-    pub position_id: Pubkey,
+    pub position_id: u64,
     pub owner: Pubkey,
     pub token: Pubkey,
     pub collateral: u64,
+    pub ctoken_shares: u64,
     pub borrowed_usdc: u64,
+    pub base_purchased: u64, // Base bought with the borrowed USDC
     pub leverage_factor: u64,
+    pub holder_fee: u64,
+    pub treasury_fee: u64,
 }
 
 #[event]
 pub struct LeveragedPositionClosed {
-    pub position_id: Pubkey,
+    pub position_id: u64,
     pub owner: Pubkey,
     pub collateral_returned: u64,
+    pub usdc_repaid: u64, // Principal plus interest returned to the lending pool
+    pub base_sold: u64, // Redeemed base sold to repay the loan
     pub yield_earned: u64,
+    pub pnl_usdc: i64, // Net of borrow interest; negative on a loss
+}
+
+#[event]
+pub struct LeveragedPositionLiquidated {
+    pub position_id: u64,
+    pub owner: Pubkey,
+    pub liquidator: Pubkey,
+    pub usdc_repaid: u64,
+    pub bad_debt: u64, // Debt the position could not cover, written off in the lending pool
+    pub base_sold: u64,
+    pub liquidator_bonus: u64, // Base paid to the liquidator
+    pub collateral_returned: u64, // Base left for the owner
 }
 
@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use forge_math::{bps_of, mul_div, to_u64, Rounding};
 
 #[account]
 pub struct Crucible {
@@ -9,26 +10,100 @@ pub struct Crucible {
     pub bump: u8,
     pub total_base_deposited: u64,
     pub total_ctoken_supply: u64,
-    pub exchange_rate: u64, // Scaled by 1_000_000 (1.0 = 1_000_000)
-    pub last_update_slot: u64,
-    pub fee_rate: u64, // Fee rate (e.g., 200 = 0.2% = 2 bps)
+    pub last_update_slot: u64, // Slot of the last vault update (ordering only)
+    pub fee_rate: u64, // Fee rate scaled by FEE_RATE_SCALE (e.g., 200 = 0.2%)
     pub paused: bool,
     pub total_leveraged_positions: u64, // Number of open LVF positions
     pub total_lp_positions: u64, // Number of open LP positions
+    pub oracle: Pubkey, // Price feed for base_mint
+    pub lp_position_nonce: u64, // Last LP position ID issued (never reused)
+    pub lp_pool: Pubkey, // forge_reactors TOKEN/USDC pool backing LP positions
+    pub authority: Pubkey, // Admin allowed to update config and pause
+    pub pause_flags: u8, // PAUSE_* scopes currently blocked
+    pub forge_protocol: Pubkey, // Linked forge_core protocol (default = not linked)
+    pub fee_vault: Pubkey, // Treasury fee vault swept by forge_core (default = none)
+    pub lvf_liquidation_threshold_bps: u64, // Debt/value ratio at which leveraged positions become liquidatable
+    pub lvf_liquidation_bonus_bps: u64, // Liquidator bonus, as a share of the base sold to repay debt
+    pub lvf_max_liquidation_slippage_bps: u64, // Max discount to the oracle price accepted when liquidations sell base
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+/// Pause scope: mint cTokens / open LP positions
+pub const PAUSE_DEPOSITS: u8 = 1 << 0;
+/// Pause scope: burn cTokens / close positions
+pub const PAUSE_WITHDRAWALS: u8 = 1 << 1;
+/// Pause scope: open leveraged positions
+pub const PAUSE_LEVERAGE: u8 = 1 << 2;
+/// All defined pause scopes
+pub const PAUSE_ALL: u8 = PAUSE_DEPOSITS | PAUSE_WITHDRAWALS | PAUSE_LEVERAGE;
+
+/// Denominator of `Crucible::fee_rate` (100_000 = 100%)
+pub const FEE_RATE_SCALE: u64 = 100_000;
+/// Highest fee rate the authority may set (10%)
+pub const MAX_FEE_RATE: u64 = 10_000;
+/// Share of each fee routed to the treasury; the rest stays in the vault for holders
+pub const TREASURY_FEE_SHARE_BPS: u64 = 2_000;
+
+/// Liquidation threshold given to new crucibles (90% debt/value)
+pub const DEFAULT_LVF_LIQUIDATION_THRESHOLD_BPS: u64 = 9_000;
+/// Liquidation bonus given to new crucibles (5%)
+pub const DEFAULT_LVF_LIQUIDATION_BONUS_BPS: u64 = 500;
+/// Highest liquidation bonus the authority may set (20%)
+pub const MAX_LVF_LIQUIDATION_BONUS_BPS: u64 = 2_000;
+/// Liquidation swap slippage allowed on new crucibles (1%)
+pub const DEFAULT_LVF_MAX_LIQUIDATION_SLIPPAGE_BPS: u64 = 100;
+/// Highest liquidation swap slippage the authority may allow (10%)
+pub const MAX_LVF_LIQUIDATION_SLIPPAGE_BPS: u64 = 1_000;
+
+/// Split `fee_rate` of `amount` into (holder share, treasury share)
+pub fn split_fee(amount: u64, fee_rate: u64) -> Result<(u64, u64)> {
+    let fee = to_u64(mul_div(amount as u128, fee_rate as u128, FEE_RATE_SCALE as u128, Rounding::Down)?)?;
+    let treasury_fee = to_u64(bps_of(fee as u128, TREASURY_FEE_SHARE_BPS, Rounding::Down)?)?;
+    Ok((fee - treasury_fee, treasury_fee))
+}
+
+/// Virtual cToken supply added to every share conversion. Together with
+/// `VIRTUAL_ASSETS` this keeps the initial 1:1 rate while making donation
+/// (inflation) attacks cost the attacker ~all of the donated amount.
+pub const VIRTUAL_SHARES: u64 = 1_000_000;
+/// Virtual base-token balance added to every share conversion
+pub const VIRTUAL_ASSETS: u64 = 1_000_000;
+
+/// cTokens minted for depositing `amount` into a vault holding `vault_amount`, rounded down
+pub fn ctokens_for_deposit(amount: u64, vault_amount: u64, ctoken_supply: u64) -> Result<u64> {
+    Ok(to_u64(mul_div(
+        amount as u128,
+        ctoken_supply as u128 + VIRTUAL_SHARES as u128,
+        vault_amount as u128 + VIRTUAL_ASSETS as u128,
+        Rounding::Down,
+    )?)?)
+}
+
+/// Base tokens redeemed for burning `ctokens`, rounded down
+pub fn base_for_ctokens(ctokens: u64, vault_amount: u64, ctoken_supply: u64) -> Result<u64> {
+    Ok(to_u64(mul_div(
+        ctokens as u128,
+        vault_amount as u128 + VIRTUAL_ASSETS as u128,
+        ctoken_supply as u128 + VIRTUAL_SHARES as u128,
+        Rounding::Down,
+    )?)?)
+}
+
+#[account]
+#[derive(Debug)]
 pub struct LPPosition {
     pub id: u64,
     pub owner: Pubkey,
-    pub base_token: String, // "FOGO" or "FORGE"
+    pub crucible: Pubkey,
+    pub base_mint: Pubkey,
     pub base_amount: u64,
     pub usdc_amount: u64,
+    pub lp_shares: u64, // Pool shares held by the crucible for this position
     pub entry_price: u64, // Entry price in USDC (scaled)
     pub current_value: u64, // Current position value in USDC
     pub yield_earned: u64, // Yield earned in USD
     pub is_open: bool,
     pub created_at: i64, // Unix timestamp
+    pub bump: u8,
 }
 
 impl Crucible {
@@ -40,11 +115,105 @@ impl Crucible {
         1 +  // bump
         8 +  // total_base_deposited
         8 +  // total_ctoken_supply
-        8 +  // exchange_rate
         8 +  // last_update_slot
         8 +  // fee_rate
         1 +  // paused
         8 +  // total_leveraged_positions
-        8;   // total_lp_positions
+        8 +  // total_lp_positions
+        32 + // oracle
+        8 +  // lp_position_nonce
+        32 + // lp_pool
+        32 + // authority
+        1 +  // pause_flags
+        32 + // forge_protocol
+        32 + // fee_vault
+        8 +  // lvf_liquidation_threshold_bps
+        8 +  // lvf_liquidation_bonus_bps
+        8;   // lvf_max_liquidation_slippage_bps
+
+    /// ID (and PDA seed) of the next LP position: one past the last issued
+    pub fn next_lp_position_id(&self) -> Result<u64> {
+        Ok(self.lp_position_nonce.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?)
+    }
 }
 
+impl LPPosition {
+    pub const LEN: usize = 8 + // discriminator
+        8 +  // id
+        32 + // owner
+        32 + // crucible
+        32 + // base_mint
+        8 +  // base_amount
+        8 +  // usdc_amount
+        8 +  // lp_shares
+        8 +  // entry_price
+        8 +  // current_value
+        8 +  // yield_earned
+        1 +  // is_open
+        8 +  // created_at
+        1;   // bump
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_fee_routes_treasury_share() {
+        // 0.2% of 1_000_000 = 2_000, 20% of it to the treasury
+        assert_eq!(split_fee(1_000_000, 200).unwrap(), (1_600, 400));
+        assert_eq!(split_fee(1_000_000, 0).unwrap(), (0, 0));
+        assert_eq!(split_fee(1_000_000, MAX_FEE_RATE).unwrap(), (80_000, 20_000));
+        // the fee rounds down, then the treasury share rounds down in favor of holders
+        assert_eq!(split_fee(499, 200).unwrap(), (0, 0));
+        assert_eq!(split_fee(500, 200).unwrap(), (1, 0));
+        assert_eq!(split_fee(2_500, 200).unwrap(), (4, 1));
+        let (holder_fee, treasury_fee) = split_fee(u64::MAX, MAX_FEE_RATE).unwrap();
+        assert_eq!(holder_fee + treasury_fee, u64::MAX / 10);
+    }
+
+    #[test]
+    fn first_deposit_mints_one_to_one() {
+        assert_eq!(ctokens_for_deposit(1_000_000, 0, 0).unwrap(), 1_000_000);
+        assert_eq!(ctokens_for_deposit(1, 0, 0).unwrap(), 1);
+        assert_eq!(base_for_ctokens(1_000_000, 1_000_000, 1_000_000).unwrap(), 1_000_000);
+    }
+
+    #[test]
+    fn share_conversions_track_vault_growth_and_round_down() {
+        // vault doubled through fees: each cToken is worth ~2 base, damped by the virtual offset
+        let (vault, supply) = (2_000_000_000u64, 1_000_000_000u64);
+        assert_eq!(ctokens_for_deposit(1_000, vault, supply).unwrap(), 500);
+        assert_eq!(base_for_ctokens(500, vault, supply).unwrap(), 999);
+        // a deposit followed by a withdrawal never returns more than went in
+        for amount in [1u64, 3, 999, 123_457] {
+            let shares = ctokens_for_deposit(amount, vault, supply).unwrap();
+            let back = base_for_ctokens(shares, vault + amount, supply + shares).unwrap();
+            assert!(back <= amount, "{amount} -> {shares} -> {back}");
+        }
+    }
+
+    #[test]
+    fn donation_attack_costs_the_attacker() {
+        // attacker mints 1 cToken, then donates to the vault before the victim deposits
+        let attacker_shares = ctokens_for_deposit(1, 0, 0).unwrap();
+        let donation = 1_000_000_000u64;
+        let vault = 1 + donation;
+
+        let victim_deposit = 1_000_000_000u64;
+        let victim_shares = ctokens_for_deposit(victim_deposit, vault, attacker_shares).unwrap();
+        assert!(victim_shares > 0);
+
+        // the donation mostly accrues to the virtual shares, not to the attacker
+        let supply = attacker_shares + victim_shares;
+        let attacker_out = base_for_ctokens(attacker_shares, vault + victim_deposit, supply).unwrap();
+        assert!(attacker_out < donation / 100, "attacker redeems {attacker_out}");
+    }
+
+    #[test]
+    fn share_conversion_overflow() {
+        assert!(ctokens_for_deposit(u64::MAX, 0, u64::MAX).is_err());
+        assert!(base_for_ctokens(u64::MAX, u64::MAX, 0).is_err());
+    }
+}
@@ -0,0 +1,409 @@
+// Summary: Constant-product (x * y = k) AMM for TOKEN/USDC pairs. Liquidity providers
+// receive LP shares minted by the pool PDA; swaps pay a fee that stays in the reserves,
+// so share value grows with volume. All user-facing amounts carry slippage limits.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, Mint, MintTo, Token, TokenAccount, Transfer};
+use forge_math::{to_u64, Rounding, BPS_SCALE};
+
+pub mod state;
+use state::*;
+
+declare_id!("4gYHfCRkwVi5xS2LotSe9oyysU27oVtBqogj4PM1hR4v");
+
+/// Shares locked forever on the first deposit so the share price cannot be inflated
+pub const MINIMUM_LIQUIDITY: u64 = 1_000;
+/// Upper bound for the swap fee (10%)
+pub const MAX_FEE_BPS: u64 = 1_000;
+
+#[program]
+pub mod forge_reactors {
+    use super::*;
+
+    pub fn initialize_pool(ctx: Context<InitializePool>, fee_bps: u64) -> Result<()> {
+        require!(fee_bps <= MAX_FEE_BPS, ReactorError::InvalidParams);
+        require_keys_neq!(ctx.accounts.token_a_mint.key(), ctx.accounts.token_b_mint.key(), ReactorError::InvalidParams);
+
+        let pool = &mut ctx.accounts.pool;
+        pool.token_a_mint = ctx.accounts.token_a_mint.key();
+        pool.token_b_mint = ctx.accounts.token_b_mint.key();
+        pool.vault_a = ctx.accounts.vault_a.key();
+        pool.vault_b = ctx.accounts.vault_b.key();
+        pool.lp_mint = ctx.accounts.lp_mint.key();
+        pool.fee_bps = fee_bps;
+        pool.total_shares = 0;
+        pool.bump = ctx.bumps.pool;
+        Ok(())
+    }
+
+    /// Deposit up to the given amounts at the current reserve ratio and mint LP shares.
+    pub fn add_liquidity(
+        ctx: Context<AddLiquidity>,
+        max_amount_a: u64,
+        max_amount_b: u64,
+        min_shares: u64,
+    ) -> Result<u64> {
+        require!(max_amount_a > 0 && max_amount_b > 0, ReactorError::InvalidAmount);
+        let pool = &mut ctx.accounts.pool;
+        let reserve_a = ctx.accounts.vault_a.amount;
+        let reserve_b = ctx.accounts.vault_b.amount;
+
+        let (amount_a, amount_b, shares) = if pool.total_shares == 0 {
+            let initial = ((max_amount_a as u128) * (max_amount_b as u128)).isqrt() as u64;
+            require!(initial > MINIMUM_LIQUIDITY, ReactorError::InsufficientLiquidity);
+            pool.total_shares = MINIMUM_LIQUIDITY;
+            (max_amount_a, max_amount_b, initial - MINIMUM_LIQUIDITY)
+        } else {
+            // Use as much of the desired amounts as the reserve ratio allows
+            let optimal_b = mul_div(max_amount_a, reserve_b, reserve_a)?;
+            let (amount_a, amount_b) = if optimal_b <= max_amount_b {
+                (max_amount_a, optimal_b)
+            } else {
+                (mul_div(max_amount_b, reserve_a, reserve_b)?, max_amount_b)
+            };
+            let shares = mul_div(amount_a, pool.total_shares, reserve_a)?
+                .min(mul_div(amount_b, pool.total_shares, reserve_b)?);
+            (amount_a, amount_b, shares)
+        };
+        require!(shares > 0, ReactorError::InvalidAmount);
+        require!(shares >= min_shares, ReactorError::SlippageExceeded);
+
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::transfer(
+            CpiContext::new(cpi_program.clone(), Transfer {
+                from: ctx.accounts.user_token_a.to_account_info(),
+                to: ctx.accounts.vault_a.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            }),
+            amount_a,
+        )?;
+        token::transfer(
+            CpiContext::new(cpi_program.clone(), Transfer {
+                from: ctx.accounts.user_token_b.to_account_info(),
+                to: ctx.accounts.vault_b.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            }),
+            amount_b,
+        )?;
+
+        let seeds = &[b"pool", pool.token_a_mint.as_ref(), pool.token_b_mint.as_ref(), &[pool.bump]];
+        let signer = &[&seeds[..]];
+        token::mint_to(
+            CpiContext::new_with_signer(cpi_program, MintTo {
+                mint: ctx.accounts.lp_mint.to_account_info(),
+                to: ctx.accounts.user_lp_account.to_account_info(),
+                authority: pool.to_account_info(),
+            }, signer),
+            shares,
+        )?;
+
+        pool.total_shares = pool.total_shares.checked_add(shares).ok_or(ReactorError::MathOverflow)?;
+        emit!(LiquidityAdded { pool: pool.key(), user: ctx.accounts.user.key(), amount_a, amount_b, shares });
+        Ok(shares)
+    }
+
+    /// Burn LP shares for a pro-rata share of both reserves.
+    pub fn remove_liquidity(
+        ctx: Context<RemoveLiquidity>,
+        shares: u64,
+        min_amount_a: u64,
+        min_amount_b: u64,
+    ) -> Result<()> {
+        require!(shares > 0, ReactorError::InvalidAmount);
+        let pool = &mut ctx.accounts.pool;
+
+        let amount_a = mul_div(shares, ctx.accounts.vault_a.amount, pool.total_shares)?;
+        let amount_b = mul_div(shares, ctx.accounts.vault_b.amount, pool.total_shares)?;
+        require!(amount_a >= min_amount_a && amount_b >= min_amount_b, ReactorError::SlippageExceeded);
+
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::burn(
+            CpiContext::new(cpi_program.clone(), Burn {
+                mint: ctx.accounts.lp_mint.to_account_info(),
+                from: ctx.accounts.user_lp_account.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            }),
+            shares,
+        )?;
+
+        let seeds = &[b"pool", pool.token_a_mint.as_ref(), pool.token_b_mint.as_ref(), &[pool.bump]];
+        let signer = &[&seeds[..]];
+        token::transfer(
+            CpiContext::new_with_signer(cpi_program.clone(), Transfer {
+                from: ctx.accounts.vault_a.to_account_info(),
+                to: ctx.accounts.user_token_a.to_account_info(),
+                authority: pool.to_account_info(),
+            }, signer),
+            amount_a,
+        )?;
+        token::transfer(
+            CpiContext::new_with_signer(cpi_program, Transfer {
+                from: ctx.accounts.vault_b.to_account_info(),
+                to: ctx.accounts.user_token_b.to_account_info(),
+                authority: pool.to_account_info(),
+            }, signer),
+            amount_b,
+        )?;
+
+        pool.total_shares = pool.total_shares.checked_sub(shares).ok_or(ReactorError::MathOverflow)?;
+        emit!(LiquidityRemoved { pool: pool.key(), user: ctx.accounts.user.key(), amount_a, amount_b, shares });
+        Ok(())
+    }
+
+    /// Swap an exact input amount; `a_to_b` selects the direction.
+    pub fn swap(ctx: Context<Swap>, amount_in: u64, min_amount_out: u64, a_to_b: bool) -> Result<u64> {
+        require!(amount_in > 0, ReactorError::InvalidAmount);
+        let pool = &ctx.accounts.pool;
+
+        let (reserve_in, reserve_out) = if a_to_b {
+            (ctx.accounts.vault_a.amount, ctx.accounts.vault_b.amount)
+        } else {
+            (ctx.accounts.vault_b.amount, ctx.accounts.vault_a.amount)
+        };
+        let amount_out = swap_output(amount_in, reserve_in, reserve_out, pool.fee_bps)?;
+        require!(amount_out > 0, ReactorError::InsufficientLiquidity);
+        require!(amount_out >= min_amount_out, ReactorError::SlippageExceeded);
+
+        let (user_in, vault_in, vault_out, user_out) = if a_to_b {
+            (&ctx.accounts.user_token_a, &ctx.accounts.vault_a, &ctx.accounts.vault_b, &ctx.accounts.user_token_b)
+        } else {
+            (&ctx.accounts.user_token_b, &ctx.accounts.vault_b, &ctx.accounts.vault_a, &ctx.accounts.user_token_a)
+        };
+
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::transfer(
+            CpiContext::new(cpi_program.clone(), Transfer {
+                from: user_in.to_account_info(),
+                to: vault_in.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            }),
+            amount_in,
+        )?;
+
+        let seeds = &[b"pool", pool.token_a_mint.as_ref(), pool.token_b_mint.as_ref(), &[pool.bump]];
+        let signer = &[&seeds[..]];
+        token::transfer(
+            CpiContext::new_with_signer(cpi_program, Transfer {
+                from: vault_out.to_account_info(),
+                to: user_out.to_account_info(),
+                authority: pool.to_account_info(),
+            }, signer),
+            amount_out,
+        )?;
+
+        emit!(Swapped { pool: pool.key(), user: ctx.accounts.user.key(), amount_in, amount_out, a_to_b });
+        Ok(amount_out)
+    }
+}
+
+/// Output of a constant-product swap after the pool fee, rounded down.
+pub fn swap_output(amount_in: u64, reserve_in: u64, reserve_out: u64, fee_bps: u64) -> Result<u64> {
+    let in_after_fee = (amount_in as u128) * (BPS_SCALE - fee_bps as u128);
+    let denominator = (reserve_in as u128) * BPS_SCALE + in_after_fee;
+    Ok(to_u64(forge_math::mul_div(in_after_fee, reserve_out as u128, denominator, Rounding::Down)?)?)
+}
+
+/// Smallest input whose `swap_output` is at least `amount_out`, rounded up.
+pub fn swap_input_for_output(amount_out: u64, reserve_in: u64, reserve_out: u64, fee_bps: u64) -> Result<u64> {
+    require!(amount_out < reserve_out, ReactorError::InsufficientLiquidity);
+    let numerator = (amount_out as u128) * BPS_SCALE;
+    let denominator = (reserve_out - amount_out) as u128 * (BPS_SCALE - fee_bps as u128);
+    require!(denominator > 0, ReactorError::InsufficientLiquidity);
+    Ok(to_u64(forge_math::mul_div(numerator, reserve_in as u128, denominator, Rounding::Up)?)?)
+}
+
+fn mul_div(a: u64, b: u64, c: u64) -> Result<u64> {
+    require!(c > 0, ReactorError::InsufficientLiquidity);
+    Ok(to_u64(forge_math::mul_div(a as u128, b as u128, c as u128, Rounding::Down)?)?)
+}
+
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = Pool::SIZE,
+        seeds = [b"pool", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+    pub token_a_mint: Account<'info, Mint>,
+    pub token_b_mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"pool_vault", pool.key().as_ref(), token_a_mint.key().as_ref()],
+        bump,
+        token::mint = token_a_mint,
+        token::authority = pool
+    )]
+    pub vault_a: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"pool_vault", pool.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        token::mint = token_b_mint,
+        token::authority = pool
+    )]
+    pub vault_b: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"lp_mint", pool.key().as_ref()],
+        bump,
+        mint::decimals = 6,
+        mint::authority = pool
+    )]
+    pub lp_mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct AddLiquidity<'info> {
+    #[account(mut, has_one = vault_a, has_one = vault_b, has_one = lp_mint)]
+    pub pool: Account<'info, Pool>,
+    pub user: Signer<'info>,
+    #[account(mut)]
+    pub vault_a: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub vault_b: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub lp_mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub user_token_a: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub user_token_b: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = lp_mint)]
+    pub user_lp_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveLiquidity<'info> {
+    #[account(mut, has_one = vault_a, has_one = vault_b, has_one = lp_mint)]
+    pub pool: Account<'info, Pool>,
+    pub user: Signer<'info>,
+    #[account(mut)]
+    pub vault_a: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub vault_b: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub lp_mint: Account<'info, Mint>,
+    #[account(mut, token::mint = pool.token_a_mint)]
+    pub user_token_a: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = pool.token_b_mint)]
+    pub user_token_b: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = lp_mint)]
+    pub user_lp_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct Swap<'info> {
+    #[account(has_one = vault_a, has_one = vault_b)]
+    pub pool: Account<'info, Pool>,
+    pub user: Signer<'info>,
+    #[account(mut)]
+    pub vault_a: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub vault_b: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = pool.token_a_mint)]
+    pub user_token_a: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = pool.token_b_mint)]
+    pub user_token_b: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[event]
+pub struct LiquidityAdded {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub shares: u64,
+}
+
+#[event]
+pub struct LiquidityRemoved {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub shares: u64,
+}
+
+#[event]
+pub struct Swapped {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub a_to_b: bool,
+}
+
+#[error_code]
+pub enum ReactorError {
+    #[msg("Invalid parameters")] InvalidParams,
+    #[msg("Invalid amount")] InvalidAmount,
+    #[msg("Insufficient pool liquidity")] InsufficientLiquidity,
+    #[msg("Slippage limit exceeded")] SlippageExceeded,
+    #[msg("Math overflow")] MathOverflow,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn swap_output_applies_fee_to_input() {
+        // no fee: 100 in against 1_000/1_000 reserves -> 100_000 / 1_100
+        assert_eq!(swap_output(100, 1_000, 1_000, 0).unwrap(), 90);
+        // 30 bps fee: 99.7 effective input -> 99_700 / 1_099.7
+        assert_eq!(swap_output(100, 1_000, 1_000, 30).unwrap(), 90);
+        assert_eq!(swap_output(1_000_000, 10_000_000, 10_000_000, 0).unwrap(), 909_090);
+        assert_eq!(swap_output(1_000_000, 10_000_000, 10_000_000, 30).unwrap(), 906_610);
+        assert_eq!(swap_output(1_000_000, 10_000_000, 10_000_000, MAX_FEE_BPS).unwrap(), 825_688);
+    }
+
+    #[test]
+    fn swap_output_rounds_down() {
+        // 1 in against 1_000/1_000 is worth 0.999 out
+        assert_eq!(swap_output(1, 1_000, 1_000, 0).unwrap(), 0);
+        assert_eq!(swap_output(0, 1_000, 1_000, 30).unwrap(), 0);
+        // never the whole output reserve
+        assert!(swap_output(u64::MAX, 1, 1_000, 0).unwrap() < 1_000);
+    }
+
+    #[test]
+    fn swap_input_for_output_rounds_in_pool_favor() {
+        for fee_bps in [0, 30, MAX_FEE_BPS] {
+            for (amount_out, reserve_in, reserve_out) in [
+                (1u64, 1_000u64, 1_000u64),
+                (500, 1_000, 1_000),
+                (906_610, 10_000_000, 10_000_000),
+                (12_345, 7_654_321, 1_234_567),
+            ] {
+                let amount_in = swap_input_for_output(amount_out, reserve_in, reserve_out, fee_bps).unwrap();
+                // the quoted input buys at least the requested output...
+                assert!(swap_output(amount_in, reserve_in, reserve_out, fee_bps).unwrap() >= amount_out);
+                // ...and one unit less does not
+                assert!(swap_output(amount_in - 1, reserve_in, reserve_out, fee_bps).unwrap() < amount_out);
+            }
+        }
+        assert_eq!(swap_input_for_output(0, 1_000, 1_000, 30).unwrap(), 0);
+    }
+
+    #[test]
+    fn swap_input_for_output_rejects_reserve_exhaustion() {
+        assert!(swap_input_for_output(1_000, 1_000, 1_000, 30).is_err());
+        assert!(swap_input_for_output(1_001, 1_000, 1_000, 30).is_err());
+        assert!(swap_input_for_output(1, 1_000, 0, 30).is_err());
+        // a 100% fee leaves no effective input
+        assert!(swap_input_for_output(1, 1_000, 1_000, BPS_SCALE as u64).is_err());
+    }
+}
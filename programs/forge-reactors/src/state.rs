@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+#[account]
+pub struct Pool {
+    pub token_a_mint: Pubkey, // e.g. base token (FOGO/FORGE)
+    pub token_b_mint: Pubkey, // e.g. USDC
+    pub vault_a: Pubkey,
+    pub vault_b: Pubkey,
+    pub lp_mint: Pubkey,
+    pub fee_bps: u64,         // swap fee kept in the reserves
+    pub total_shares: u64,    // LP supply plus permanently locked MINIMUM_LIQUIDITY
+    pub bump: u8,
+}
+
+impl Pool {
+    pub const SIZE: usize = 8 + // discriminator
+        32 + // token_a_mint
+        32 + // token_b_mint
+        32 + // vault_a
+        32 + // vault_b
+        32 + // lp_mint
+        8 +  // fee_bps
+        8 +  // total_shares
+        1;   // bump
+}
@@ -1,28 +1,63 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Mint, MintTo, Transfer};
+use anchor_spl::token::{self, Token, TokenAccount, Mint, MintTo, Burn, Transfer};
+use forge_math::{compound_growth, mul_div, to_u64, Decimal, Rounding, BPS_SCALE};
 
-declare_id!("LendingPool111111111111111111111111111");
+declare_id!("3uZbor9f1fyzPq419Z5eu8bJFxjpA6XtcENNDFuvbn4Q");
 
 #[program]
 pub mod lending_pool_usdc {
     use super::*;
 
     /// Initialize the lending pool with USDC
-    pub fn initialize(ctx: Context<Initialize>, initial_liquidity: u64) -> Result<()> {
+    pub fn initialize(ctx: Context<Initialize>, rate_model: RateModel) -> Result<()> {
+        rate_model.validate()?;
+
         let pool = &mut ctx.accounts.pool;
+        pool.authority = ctx.accounts.authority.key();
         pool.usdc_mint = ctx.accounts.usdc_mint.key();
-        pool.total_liquidity = initial_liquidity;
+        pool.lp_mint = ctx.accounts.lp_mint.key();
+        pool.total_liquidity = 0;
         pool.total_borrowed = 0;
-        pool.borrow_rate = 10; // 10% APY (scaled by 100)
-        pool.lender_rate = 5; // 5% APY for lenders (scaled by 100)
+        pool.total_shares = 0;
+        pool.borrow_index = Decimal::ONE.to_scaled();
+        pool.rate_model = rate_model;
+        pool.last_accrued_ts = Clock::get()?.unix_timestamp as u64;
         pool.bump = ctx.bumps.pool;
         Ok(())
     }
 
+    /// Whitelist a borrower (typically a program PDA that signs via CPI) up to `borrow_limit`
+    pub fn authorize_borrower(ctx: Context<AuthorizeBorrower>, borrower: Pubkey, borrow_limit: u64) -> Result<()> {
+        let authorization = &mut ctx.accounts.authorization;
+        authorization.borrower = borrower;
+        authorization.borrow_limit = borrow_limit;
+        authorization.bump = ctx.bumps.authorization;
+
+        emit!(BorrowerAuthorized { borrower, borrow_limit });
+        Ok(())
+    }
+
+    /// Remove a borrower from the whitelist; outstanding debt can still be repaid
+    pub fn revoke_borrower(ctx: Context<RevokeBorrower>) -> Result<()> {
+        emit!(BorrowerRevoked { borrower: ctx.accounts.authorization.borrower });
+        Ok(())
+    }
+
+    /// Accrue interest up to the current timestamp (permissionless)
+    pub fn accrue_interest(ctx: Context<AccrueInterest>) -> Result<()> {
+        accrue_pool(&mut ctx.accounts.pool)
+    }
+
     /// Deposit USDC to the lending pool (lenders)
     pub fn deposit_usdc(ctx: Context<DepositUSDC>, amount: u64) -> Result<()> {
+        require!(amount > 0, LendingPoolError::InvalidAmount);
         let pool = &mut ctx.accounts.pool;
-        
+        accrue_pool(pool)?;
+
+        // Shares are priced against liquidity including accrued interest
+        let shares = shares_for_deposit(pool, amount)?;
+        require!(shares > 0, LendingPoolError::InvalidAmount);
+
         // Transfer USDC from user to pool vault
         let cpi_accounts = Transfer {
             from: ctx.accounts.user_usdc_account.to_account_info(),
@@ -33,37 +68,100 @@ pub mod lending_pool_usdc {
         let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
         token::transfer(cpi_ctx, amount)?;
 
+        // Mint LP shares to the lender
+        let seeds = &[b"pool".as_ref(), &[pool.bump]];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = MintTo {
+            mint: ctx.accounts.lp_mint.to_account_info(),
+            to: ctx.accounts.user_lp_account.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer);
+        token::mint_to(cpi_ctx, shares)?;
+
         // Update pool state
         pool.total_liquidity = pool.total_liquidity
             .checked_add(amount)
             .ok_or(ProgramError::ArithmeticOverflow)?;
+        pool.total_shares = pool.total_shares
+            .checked_add(shares)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
 
         emit!(USDCDeposited {
             lender: ctx.accounts.user.key(),
             amount,
+            shares,
             total_liquidity: pool.total_liquidity,
         });
 
         Ok(())
     }
 
-    /// Borrow USDC from the lending pool
+    /// Redeem LP shares for USDC plus accrued interest, limited by idle liquidity
+    pub fn withdraw_usdc(ctx: Context<WithdrawUSDC>, shares: u64) -> Result<()> {
+        require!(shares > 0, LendingPoolError::InvalidAmount);
+        let pool = &mut ctx.accounts.pool;
+        accrue_pool(pool)?;
+
+        let amount = usdc_for_shares(pool, shares)?;
+        require!(amount > 0, LendingPoolError::InvalidAmount);
+        require!(
+            amount <= pool.available_liquidity(),
+            LendingPoolError::InsufficientLiquidity
+        );
+
+        // Burn the lender's shares
+        let cpi_accounts = Burn {
+            mint: ctx.accounts.lp_mint.to_account_info(),
+            from: ctx.accounts.user_lp_account.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        token::burn(CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts), shares)?;
+
+        // Transfer USDC from pool vault to lender
+        let seeds = &[b"pool".as_ref(), &[pool.bump]];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.pool_vault.to_account_info(),
+            to: ctx.accounts.user_usdc_account.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer);
+        token::transfer(cpi_ctx, amount)?;
+
+        // Update pool state
+        pool.total_liquidity = pool.total_liquidity.saturating_sub(amount);
+        pool.total_shares = pool.total_shares.saturating_sub(shares);
+
+        emit!(USDCWithdrawn {
+            lender: ctx.accounts.user.key(),
+            amount,
+            shares,
+            total_liquidity: pool.total_liquidity,
+        });
+
+        Ok(())
+    }
+
+    /// Borrow USDC from the lending pool (whitelisted borrowers only)
     pub fn borrow_usdc(ctx: Context<BorrowUSDC>, amount: u64) -> Result<()> {
+        require!(amount > 0, LendingPoolError::InvalidAmount);
         let pool = &mut ctx.accounts.pool;
+        accrue_pool(pool)?;
 
         require!(
-            amount <= pool.total_liquidity.checked_sub(pool.total_borrowed).unwrap_or(0),
+            amount <= pool.available_liquidity(),
             LendingPoolError::InsufficientLiquidity
         );
 
         // Transfer USDC from pool vault to borrower
-        let seeds = &[b"pool", &[pool.bump]];
+        let seeds = &[b"pool".as_ref(), &[pool.bump]];
         let signer = &[&seeds[..]];
 
         let cpi_accounts = Transfer {
             from: ctx.accounts.pool_vault.to_account_info(),
             to: ctx.accounts.borrower_usdc_account.to_account_info(),
-            authority: ctx.accounts.pool_authority.to_account_info(),
+            authority: pool.to_account_info(),
         };
         let cpi_program = ctx.accounts.token_program.to_account_info();
         let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
@@ -74,12 +172,19 @@ pub mod lending_pool_usdc {
             .checked_add(amount)
             .ok_or(ProgramError::ArithmeticOverflow)?;
 
-        // Record borrower debt
+        // Record borrower debt in index-scaled units so it grows with the borrow index
+        let scaled = Decimal::from_scaled(pool.borrow_index).div_amount(amount as u128, Rounding::Up)?;
         let borrower_account = &mut ctx.accounts.borrower_account;
         borrower_account.borrower = ctx.accounts.borrower.key();
-        borrower_account.amount_borrowed = borrower_account.amount_borrowed
-            .checked_add(amount)
+        borrower_account.scaled_debt = borrower_account.scaled_debt
+            .checked_add(scaled)
             .ok_or(ProgramError::ArithmeticOverflow)?;
+        borrower_account.bump = ctx.bumps.borrower_account;
+
+        require!(
+            borrower_account.debt(pool.borrow_index)? <= ctx.accounts.authorization.borrow_limit,
+            LendingPoolError::BorrowLimitExceeded
+        );
 
         emit!(USDCBorrowed {
             borrower: ctx.accounts.borrower.key(),
@@ -90,15 +195,16 @@ pub mod lending_pool_usdc {
         Ok(())
     }
 
-    /// Repay borrowed USDC
+    /// Repay borrowed USDC; amounts above the outstanding debt are capped
     pub fn repay_usdc(ctx: Context<RepayUSDC>, amount: u64) -> Result<()> {
+        require!(amount > 0, LendingPoolError::InvalidAmount);
         let pool = &mut ctx.accounts.pool;
-        let borrower_account = &mut ctx.accounts.borrower_account;
+        accrue_pool(pool)?;
 
-        require!(
-            amount <= borrower_account.amount_borrowed,
-            LendingPoolError::RepayAmountExceedsDebt
-        );
+        let borrower_account = &mut ctx.accounts.borrower_account;
+        let owed = borrower_account.debt(pool.borrow_index)?;
+        require!(owed > 0, LendingPoolError::NoDebt);
+        let repay_amount = amount.min(owed);
 
         // Transfer USDC from borrower to pool vault
         let cpi_accounts = Transfer {
@@ -108,21 +214,61 @@ pub mod lending_pool_usdc {
         };
         let cpi_program = ctx.accounts.token_program.to_account_info();
         let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        token::transfer(cpi_ctx, amount)?;
+        token::transfer(cpi_ctx, repay_amount)?;
 
         // Update pool state
-        pool.total_borrowed = pool.total_borrowed
-            .checked_sub(amount)
-            .ok_or(0);
+        pool.total_borrowed = pool.total_borrowed.saturating_sub(repay_amount);
 
-        borrower_account.amount_borrowed = borrower_account.amount_borrowed
-            .checked_sub(amount)
-            .ok_or(0);
+        if repay_amount == owed {
+            borrower_account.scaled_debt = 0;
+        } else {
+            let scaled = Decimal::from_scaled(pool.borrow_index)
+                .div_amount(repay_amount as u128, Rounding::Down)?;
+            borrower_account.scaled_debt = borrower_account.scaled_debt.saturating_sub(scaled);
+        }
 
         emit!(USDCRepaid {
             borrower: ctx.accounts.borrower.key(),
-            amount,
-            remaining_debt: borrower_account.amount_borrowed,
+            amount: repay_amount,
+            remaining_debt: borrower_account.debt(pool.borrow_index)?,
+        });
+
+        Ok(())
+    }
+
+    /// Write off debt the borrower can no longer cover once its collateral has been
+    /// liquidated; lenders absorb the loss through `total_liquidity`. Only the borrower
+    /// (a whitelisted program PDA signing via CPI) can write off its own debt, and its
+    /// `borrow_limit` shrinks by the same amount so the freed headroom can't be re-borrowed.
+    pub fn write_off_bad_debt(ctx: Context<WriteOffBadDebt>, amount: u64) -> Result<()> {
+        require!(amount > 0, LendingPoolError::InvalidAmount);
+        let pool = &mut ctx.accounts.pool;
+        accrue_pool(pool)?;
+
+        let borrower_account = &mut ctx.accounts.borrower_account;
+        let owed = borrower_account.debt(pool.borrow_index)?;
+        require!(owed > 0, LendingPoolError::NoDebt);
+        let written_off = amount.min(owed);
+
+        if written_off == owed {
+            borrower_account.scaled_debt = 0;
+        } else {
+            let scaled = Decimal::from_scaled(pool.borrow_index)
+                .div_amount(written_off as u128, Rounding::Down)?;
+            borrower_account.scaled_debt = borrower_account.scaled_debt.saturating_sub(scaled);
+        }
+        pool.total_borrowed = pool.total_borrowed.saturating_sub(written_off);
+        pool.total_liquidity = pool.total_liquidity.saturating_sub(written_off);
+
+        let authorization = &mut ctx.accounts.authorization;
+        authorization.borrow_limit = authorization.borrow_limit.saturating_sub(written_off);
+
+        emit!(BadDebtWrittenOff {
+            borrower: ctx.accounts.borrower.key(),
+            amount: written_off,
+            remaining_debt: borrower_account.debt(pool.borrow_index)?,
+            total_liquidity: pool.total_liquidity,
+            borrow_limit: authorization.borrow_limit,
         });
 
         Ok(())
@@ -130,12 +276,70 @@ pub mod lending_pool_usdc {
 
     /// Get available liquidity (view function simulation)
     pub fn get_available_liquidity(ctx: Context<GetAvailableLiquidity>) -> Result<u64> {
-        let pool = &ctx.accounts.pool;
-        let available = pool.total_liquidity
-            .checked_sub(pool.total_borrowed)
-            .unwrap_or(0);
-        Ok(available)
+        Ok(ctx.accounts.pool.available_liquidity())
+    }
+}
+
+/// Compound the borrow index up to now; lenders earn the interest through
+/// `total_liquidity`, which raises the value of every lender share.
+fn accrue_pool(pool: &mut Account<LendingPool>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp as u64;
+    if now <= pool.last_accrued_ts { return Ok(()); }
+
+    let seconds = now - pool.last_accrued_ts;
+    let annual_rate = pool.borrow_rate()?;
+    let growth = compound_growth(annual_rate, seconds)?;
+
+    let old_index = pool.borrow_index;
+    pool.borrow_index = Decimal::from_scaled(old_index)
+        .checked_mul(growth, Rounding::Down)?
+        .to_scaled();
+
+    // outstanding debt grows with the index; the interest is owed to lenders
+    let old_borrowed = pool.total_borrowed;
+    pool.total_borrowed = to_u64(mul_div(old_borrowed as u128, pool.borrow_index, old_index, Rounding::Down)?)?;
+    let interest = pool.total_borrowed - old_borrowed;
+    pool.total_liquidity = pool.total_liquidity
+        .checked_add(interest)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    pool.last_accrued_ts = now;
+
+    if interest > 0 {
+        emit!(InterestAccrued {
+            borrow_rate_bps: annual_rate.to_bps(Rounding::Down)?,
+            borrow_index: pool.borrow_index,
+            interest,
+            total_liquidity: pool.total_liquidity,
+        });
+    }
+    Ok(())
+}
+
+/// Lender shares minted for `amount` USDC at the current share price.
+/// Shares mint at par only into an empty pool; once bad debt has wiped out all
+/// liquidity behind existing shares, deposits are refused rather than diluted.
+fn shares_for_deposit(pool: &LendingPool, amount: u64) -> Result<u64> {
+    if pool.total_shares == 0 {
+        return Ok(amount);
     }
+    require!(pool.total_liquidity > 0, LendingPoolError::PoolInsolvent);
+    Ok(to_u64(mul_div(
+        amount as u128,
+        pool.total_shares as u128,
+        pool.total_liquidity as u128,
+        Rounding::Down,
+    )?)?)
+}
+
+/// USDC redeemable for `shares` at the current share price.
+fn usdc_for_shares(pool: &LendingPool, shares: u64) -> Result<u64> {
+    require!(shares <= pool.total_shares, LendingPoolError::InvalidAmount);
+    Ok(to_u64(mul_div(
+        shares as u128,
+        pool.total_liquidity as u128,
+        pool.total_shares as u128,
+        Rounding::Down,
+    )?)?)
 }
 
 #[derive(Accounts)]
@@ -151,15 +355,81 @@ pub struct Initialize<'info> {
 
     pub usdc_mint: Account<'info, Mint>,
 
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"vault", pool.key().as_ref()],
+        bump,
+        token::mint = usdc_mint,
+        token::authority = pool,
+    )]
+    pub pool_vault: Account<'info, TokenAccount>,
+
+    /// Lender share mint; shares use the USDC mint's decimals
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"lp_mint", pool.key().as_ref()],
+        bump,
+        mint::decimals = usdc_mint.decimals,
+        mint::authority = pool,
+    )]
+    pub lp_mint: Account<'info, Mint>,
+
     #[account(mut)]
     pub authority: Signer<'info>,
 
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct DepositUSDC<'info> {
+#[instruction(borrower: Pubkey)]
+pub struct AuthorizeBorrower<'info> {
+    #[account(seeds = [b"pool"], bump = pool.bump, has_one = authority)]
+    pub pool: Account<'info, LendingPool>,
+
     #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + BorrowerAuthorization::LEN,
+        seeds = [b"authorized_borrower", borrower.as_ref()],
+        bump,
+    )]
+    pub authorization: Account<'info, BorrowerAuthorization>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeBorrower<'info> {
+    #[account(seeds = [b"pool"], bump = pool.bump, has_one = authority)]
+    pub pool: Account<'info, LendingPool>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"authorized_borrower", authorization.borrower.as_ref()],
+        bump = authorization.bump,
+    )]
+    pub authorization: Account<'info, BorrowerAuthorization>,
+}
+
+#[derive(Accounts)]
+pub struct AccrueInterest<'info> {
+    #[account(mut, seeds = [b"pool"], bump = pool.bump)]
+    pub pool: Account<'info, LendingPool>,
+}
+
+#[derive(Accounts)]
+pub struct DepositUSDC<'info> {
+    #[account(mut, seeds = [b"pool"], bump = pool.bump)]
     pub pool: Account<'info, LendingPool>,
 
     #[account(mut)]
@@ -175,28 +445,61 @@ pub struct DepositUSDC<'info> {
     )]
     pub pool_vault: Account<'info, TokenAccount>,
 
+    #[account(mut, address = pool.lp_mint)]
+    pub lp_mint: Account<'info, Mint>,
+
+    #[account(mut, token::mint = lp_mint)]
+    pub user_lp_account: Account<'info, TokenAccount>,
+
     pub token_program: Program<'info, Token>,
 }
 
 #[derive(Accounts)]
-pub struct BorrowUSDC<'info> {
-    #[account(mut)]
+pub struct WithdrawUSDC<'info> {
+    #[account(mut, seeds = [b"pool"], bump = pool.bump)]
     pub pool: Account<'info, LendingPool>,
 
+    pub user: Signer<'info>,
+
+    #[account(mut, token::mint = pool.usdc_mint)]
+    pub user_usdc_account: Account<'info, TokenAccount>,
+
     #[account(
         mut,
-        seeds = [b"pool"],
-        bump = pool.bump,
+        seeds = [b"vault", pool.key().as_ref()],
+        bump,
     )]
-    /// CHECK: Pool authority PDA
-    pub pool_authority: UncheckedAccount<'info>,
+    pub pool_vault: Account<'info, TokenAccount>,
 
-    #[account(mut)]
+    #[account(mut, address = pool.lp_mint)]
+    pub lp_mint: Account<'info, Mint>,
+
+    #[account(mut, token::mint = lp_mint)]
+    pub user_lp_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct BorrowUSDC<'info> {
+    #[account(mut, seeds = [b"pool"], bump = pool.bump)]
+    pub pool: Account<'info, LendingPool>,
+
+    /// Whitelisted borrower; a program PDA proves itself by signing with its seeds via CPI
     pub borrower: Signer<'info>,
 
+    #[account(
+        seeds = [b"authorized_borrower", borrower.key().as_ref()],
+        bump = authorization.bump,
+    )]
+    pub authorization: Account<'info, BorrowerAuthorization>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
     #[account(
         init_if_needed,
-        payer = borrower,
+        payer = payer,
         space = 8 + BorrowerAccount::LEN,
         seeds = [b"borrower", borrower.key().as_ref()],
         bump,
@@ -219,7 +522,7 @@ pub struct BorrowUSDC<'info> {
 
 #[derive(Accounts)]
 pub struct RepayUSDC<'info> {
-    #[account(mut)]
+    #[account(mut, seeds = [b"pool"], bump = pool.bump)]
     pub pool: Account<'info, LendingPool>,
 
     #[account(mut)]
@@ -228,7 +531,7 @@ pub struct RepayUSDC<'info> {
     #[account(
         mut,
         seeds = [b"borrower", borrower.key().as_ref()],
-        bump,
+        bump = borrower_account.bump,
     )]
     pub borrower_account: Account<'info, BorrowerAccount>,
 
@@ -245,39 +548,152 @@ pub struct RepayUSDC<'info> {
     pub token_program: Program<'info, Token>,
 }
 
+#[derive(Accounts)]
+pub struct WriteOffBadDebt<'info> {
+    #[account(mut, seeds = [b"pool"], bump = pool.bump)]
+    pub pool: Account<'info, LendingPool>,
+
+    pub borrower: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"authorized_borrower", borrower.key().as_ref()],
+        bump = authorization.bump,
+    )]
+    pub authorization: Account<'info, BorrowerAuthorization>,
+
+    #[account(
+        mut,
+        seeds = [b"borrower", borrower.key().as_ref()],
+        bump = borrower_account.bump,
+    )]
+    pub borrower_account: Account<'info, BorrowerAccount>,
+}
+
 #[derive(Accounts)]
 pub struct GetAvailableLiquidity<'info> {
     pub pool: Account<'info, LendingPool>,
 }
 
+/// Kinked utilization curve; all rates are annual, in basis points.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct RateModel {
+    pub base_rate_bps: u64,
+    pub slope1_bps: u64,
+    pub slope2_bps: u64,
+    pub optimal_utilization_bps: u64,
+}
+
+impl RateModel {
+    pub const LEN: usize = 8 + 8 + 8 + 8;
+
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.optimal_utilization_bps > 0 && self.optimal_utilization_bps as u128 <= BPS_SCALE,
+            LendingPoolError::InvalidRateModel
+        );
+        require!(self.base_rate_bps <= 1_000_000, LendingPoolError::InvalidRateModel);
+        require!(self.slope1_bps <= self.slope2_bps, LendingPoolError::InvalidRateModel);
+        require!(self.slope2_bps <= 1_000_000, LendingPoolError::InvalidRateModel);
+        Ok(())
+    }
+}
+
 #[account]
 pub struct LendingPool {
+    pub authority: Pubkey,
     pub usdc_mint: Pubkey,
-    pub total_liquidity: u64,
-    pub total_borrowed: u64,
-    pub borrow_rate: u64, // 10 = 10% APY (scaled by 100)
-    pub lender_rate: u64, // 5 = 5% APY (scaled by 100)
+    pub lp_mint: Pubkey,      // lender share mint, supply == total_shares
+    pub total_liquidity: u64, // owed to lenders: deposits plus accrued interest
+    pub total_borrowed: u64,  // outstanding debt including accrued interest
+    pub total_shares: u64,    // lender shares, each worth total_liquidity / total_shares
+    pub borrow_index: u128,   // forge_math::SCALE at init, compounds with the borrow rate
+    pub rate_model: RateModel,
+    pub last_accrued_ts: u64,
     pub bump: u8,
 }
 
 impl LendingPool {
-    pub const LEN: usize = 32 + 8 + 8 + 8 + 8 + 1;
+    pub const LEN: usize = 32 + 32 + 32 + 8 + 8 + 8 + 16 + RateModel::LEN + 8 + 1;
+
+    pub fn available_liquidity(&self) -> u64 {
+        self.total_liquidity.saturating_sub(self.total_borrowed)
+    }
+
+    /// total_borrowed / total_liquidity
+    pub fn utilization(&self) -> Result<Decimal> {
+        if self.total_liquidity == 0 {
+            return Ok(Decimal::ZERO);
+        }
+        Ok(Decimal::from_ratio(self.total_borrowed as u128, self.total_liquidity as u128, Rounding::Down)?)
+    }
+
+    /// Annual borrow rate at the current utilization.
+    pub fn borrow_rate(&self) -> Result<Decimal> {
+        let util = self.utilization()?;
+        let model = &self.rate_model;
+        let optimal = Decimal::from_bps(model.optimal_utilization_bps);
+        let base = Decimal::from_bps(model.base_rate_bps);
+        let slope1 = Decimal::from_bps(model.slope1_bps);
+        let slope2 = Decimal::from_bps(model.slope2_bps);
+
+        let rate = if util <= optimal {
+            base.checked_add(util.checked_mul(slope1, Rounding::Down)?.checked_div(optimal, Rounding::Down)?)?
+        } else {
+            // util > optimal implies optimal < 1, so the divisor is non-zero
+            let excess = util.checked_sub(optimal)?
+                .checked_div(Decimal::ONE.checked_sub(optimal)?, Rounding::Down)?;
+            base.checked_add(slope1)?.checked_add(excess.checked_mul(slope2, Rounding::Down)?)?
+        };
+        Ok(rate)
+    }
+
+    /// Annual rate earned by lenders: the borrow rate spread over all liquidity.
+    pub fn lender_rate(&self) -> Result<Decimal> {
+        Ok(self.borrow_rate()?.checked_mul(self.utilization()?, Rounding::Down)?)
+    }
+}
+
+#[account]
+pub struct BorrowerAuthorization {
+    pub borrower: Pubkey,
+    pub borrow_limit: u64, // max outstanding debt including interest
+    pub bump: u8,
+}
+
+impl BorrowerAuthorization {
+    pub const LEN: usize = 32 + 8 + 1;
 }
 
 #[account]
 pub struct BorrowerAccount {
     pub borrower: Pubkey,
-    pub amount_borrowed: u64,
+    pub scaled_debt: u128, // debt / borrow_index at the time of borrowing
+    pub bump: u8,
 }
 
 impl BorrowerAccount {
-    pub const LEN: usize = 32 + 8;
+    pub const LEN: usize = 32 + 16 + 1;
+
+    /// Current debt including accrued interest, rounded up.
+    pub fn debt(&self, borrow_index: u128) -> Result<u64> {
+        Ok(to_u64(Decimal::from_scaled(borrow_index).mul_amount(self.scaled_debt, Rounding::Up)?)?)
+    }
 }
 
 #[event]
 pub struct USDCDeposited {
     pub lender: Pubkey,
     pub amount: u64,
+    pub shares: u64,
+    pub total_liquidity: u64,
+}
+
+#[event]
+pub struct USDCWithdrawn {
+    pub lender: Pubkey,
+    pub amount: u64,
+    pub shares: u64,
     pub total_liquidity: u64,
 }
 
@@ -295,11 +711,46 @@ pub struct USDCRepaid {
     pub remaining_debt: u64,
 }
 
+#[event]
+pub struct BadDebtWrittenOff {
+    pub borrower: Pubkey,
+    pub amount: u64,
+    pub remaining_debt: u64,
+    pub total_liquidity: u64,
+    pub borrow_limit: u64,
+}
+
+#[event]
+pub struct BorrowerAuthorized {
+    pub borrower: Pubkey,
+    pub borrow_limit: u64,
+}
+
+#[event]
+pub struct BorrowerRevoked {
+    pub borrower: Pubkey,
+}
+
+#[event]
+pub struct InterestAccrued {
+    pub borrow_rate_bps: u64,
+    pub borrow_index: u128,
+    pub interest: u64,
+    pub total_liquidity: u64,
+}
+
 #[error_code]
 pub enum LendingPoolError {
     #[msg("Insufficient liquidity in pool")]
     InsufficientLiquidity,
-    #[msg("Repay amount exceeds debt")]
-    RepayAmountExceedsDebt,
+    #[msg("Invalid amount")]
+    InvalidAmount,
+    #[msg("No outstanding debt")]
+    NoDebt,
+    #[msg("Invalid interest rate model")]
+    InvalidRateModel,
+    #[msg("Borrow exceeds the borrower's authorized limit")]
+    BorrowLimitExceeded,
+    #[msg("Pool has outstanding shares but no liquidity")]
+    PoolInsolvent,
 }
-
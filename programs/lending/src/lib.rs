@@ -1,24 +1,30 @@
 // Summary: Anchor program implementing isolated lending markets with supply, borrow,
-// repay, withdraw and interest accrual. Uses u128 fixed-point RATE_SCALE = 1e9
-// to match existing crucible cToken rate scale. Includes pause and admin hooks.
+// repay, withdraw and interest accrual. Borrowers lock receipt tokens as collateral
+// and carry debt scaled against a compounding borrow index; receipt shares are priced by
+// a supply index net of the reserve factor. Rates and indexes use the shared
+// forge_math Decimal (1e9 fixed point). Includes pause and admin hooks.
 
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer, MintTo, Burn};
+use forge_math::{bps_of, compound_growth, mul_div, to_u64, Decimal, Rounding, BPS_SCALE};
+use forge_core::ForgeProtocol;
+use oracle::state::PriceFeed;
 
+pub mod obligation;
 pub mod state;
+use obligation::*;
 use state::*;
 
 declare_id!("LenD1ng111111111111111111111111111111111111");
 
-pub const RATE_SCALE: u128 = 1_000_000_000u128; // 1e9 fixed point for rates
-
 #[program]
 pub mod lending {
     use super::*;
 
-    pub fn initialize_market(ctx: Context<InitializeMarket>, params: InitializeMarketParams) -> Result<()> {
+    pub fn initialize_market(ctx: Context<InitializeMarket>, params: MarketParams) -> Result<()> {
         let market = &mut ctx.accounts.market;
-        require!(params.base_rate_bps <= 1_000_000, LendingError::InvalidParams);
+        validate_market_params(&params)?;
+        let interest_model = params.interest_model();
 
         market.authority = ctx.accounts.authority.key();
         market.base_mint = ctx.accounts.base_mint.key();
@@ -26,16 +32,20 @@ pub mod lending {
         market.receipt_mint = ctx.accounts.receipt_mint.key();
         market.total_supply = 0;
         market.total_borrowed = 0;
-        market.accumulated_index = RATE_SCALE; // start at 1.0
+        market.borrow_index = Decimal::ONE.to_scaled(); // start at 1.0
+        market.supply_index = Decimal::ONE.to_scaled();
+        market.supply_index_carry = 0;
+        market.reserve_factor_bps = params.reserve_factor_bps;
+        market.total_reserves = 0;
         market.last_accrued_ts = Clock::get()?.unix_timestamp as u64;
-        market.interest_model = InterestRateModelConfig {
-            base_rate_bps: params.base_rate_bps,
-            slope1_bps: params.slope1_bps,
-            slope2_bps: params.slope2_bps,
-            kink_bps: params.kink_bps,
-        };
+        market.interest_model = interest_model;
+        market.ltv_bps = params.ltv_bps;
         market.liquidation_threshold_bps = params.liquidation_threshold_bps;
+        market.liquidation_bonus_bps = params.liquidation_bonus_bps;
         market.paused = false;
+        market.credit_delegate = Pubkey::default();
+        market.price_feed = ctx.accounts.price_feed.key();
+        market.decimals = ctx.accounts.base_mint.decimals;
         market.bump = ctx.bumps.market;
 
         Ok(())
@@ -48,52 +58,54 @@ pub mod lending {
         Ok(())
     }
 
-    pub fn accrue_interest(ctx: Context<AccrueInterest>) -> Result<()> {
+    /// Retune the rate model, collateral terms and reserve factor (authority only).
+    /// Interest up to now is accrued at the old rates before the switch.
+    pub fn update_market_params(ctx: Context<UpdateMarketParams>, params: MarketParams) -> Result<()> {
         let market = &mut ctx.accounts.market;
-        let now = Clock::get()?.unix_timestamp as u64;
-        if now <= market.last_accrued_ts { return Ok(()); }
-
-        // utilization = borrowed / max(1, supply)
-        let supply = market.total_supply.max(1);
-        let util_scaled = (market.total_borrowed as u128)
-            .checked_mul(RATE_SCALE).unwrap()
-            .checked_div(supply as u128).unwrap();
-
-        // piecewise interest rate
-        let kink_scaled = (market.interest_model.kink_bps as u128) * RATE_SCALE / 10_000u128;
-        let base_scaled = (market.interest_model.base_rate_bps as u128) * RATE_SCALE / 10_000u128;
-        let slope1_scaled = (market.interest_model.slope1_bps as u128) * RATE_SCALE / 10_000u128;
-        let slope2_scaled = (market.interest_model.slope2_bps as u128) * RATE_SCALE / 10_000u128;
-
-        let ir_scaled = if util_scaled <= kink_scaled {
-            base_scaled + util_scaled.checked_mul(slope1_scaled).unwrap() / RATE_SCALE
-        } else {
-            let pre = base_scaled + kink_scaled.checked_mul(slope1_scaled).unwrap() / RATE_SCALE;
-            let delta = util_scaled - kink_scaled;
-            pre + delta.checked_mul(slope2_scaled).unwrap() / RATE_SCALE
+        validate_market_params(&params)?;
+        let interest_model = params.interest_model();
+
+        accrue_market(market, ctx.accounts.vault.amount)?;
+
+        let event = MarketParamsUpdated {
+            market: market.key(),
+            old_interest_model: market.interest_model,
+            new_interest_model: interest_model,
+            old_ltv_bps: market.ltv_bps,
+            new_ltv_bps: params.ltv_bps,
+            old_liquidation_threshold_bps: market.liquidation_threshold_bps,
+            new_liquidation_threshold_bps: params.liquidation_threshold_bps,
+            old_liquidation_bonus_bps: market.liquidation_bonus_bps,
+            new_liquidation_bonus_bps: params.liquidation_bonus_bps,
+            old_reserve_factor_bps: market.reserve_factor_bps,
+            new_reserve_factor_bps: params.reserve_factor_bps,
         };
+        market.interest_model = interest_model;
+        market.ltv_bps = params.ltv_bps;
+        market.liquidation_threshold_bps = params.liquidation_threshold_bps;
+        market.liquidation_bonus_bps = params.liquidation_bonus_bps;
+        market.reserve_factor_bps = params.reserve_factor_bps;
 
-        // simple linear accrual per second on index: index *= (1 + ir_per_sec)
-        // ir_scaled is annualized in fixed-point; convert roughly to per-second
-        let seconds = now - market.last_accrued_ts;
-        let per_sec_scaled = ir_scaled / (365u128 * 24 * 60 * 60);
-        let increment = market.accumulated_index
-            .checked_mul(per_sec_scaled).unwrap()
-            .checked_mul(seconds as u128).unwrap()
-            .checked_div(RATE_SCALE).unwrap();
-
-        market.accumulated_index = market.accumulated_index.checked_add(increment).unwrap();
-        market.last_accrued_ts = now;
+        emit!(event);
         Ok(())
     }
 
+    pub fn accrue_interest(ctx: Context<AccrueInterest>) -> Result<()> {
+        accrue_market(&mut ctx.accounts.market, ctx.accounts.vault.amount)
+    }
+
     pub fn supply(ctx: Context<Supply>, amount: u64) -> Result<()> {
         let market = &mut ctx.accounts.market;
         require!(!market.paused, LendingError::Paused);
         require!(amount > 0, LendingError::InvalidAmount);
 
         // Accrue before state changes
-        accrue_interest(Context::new(ctx.program_id, AccrueInterest { market: ctx.accounts.market.clone() }, vec![]))?;
+        accrue_market(market, ctx.accounts.vault.amount)?;
+
+        // Shares are priced at the supply index
+        let rate = exchange_rate(market);
+        let receipt_amount = to_u64(rate.div_amount(amount as u128, Rounding::Down)?)?;
+        require!(receipt_amount > 0, LendingError::InvalidAmount);
 
         // Transfer base tokens to vault
         let cpi_accounts = Transfer {
@@ -103,9 +115,6 @@ pub mod lending {
         };
         token::transfer(CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts), amount)?;
 
-        // Compute receipt to mint using index (1:1 scaled by index)
-        let receipt_amount = amount; // For MVP, 1:1; redeem logic will apply index
-
         // Mint receipt token to user (mint authority = market PDA)
         let seeds = &[b"market", market.base_mint.as_ref(), &[market.bump]];
         let signer = &[&seeds[..]];
@@ -116,14 +125,22 @@ pub mod lending {
         };
         token::mint_to(CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), mint_cpi, signer), receipt_amount)?;
 
-        market.total_supply = market.total_supply.checked_add(amount as u128).unwrap();
-        emit!(SupplyEvent { user: ctx.accounts.user.key(), amount });
+        market.total_supply = market.total_supply.checked_add(receipt_amount as u128).ok_or(LendingError::MathOverflow)?;
+        emit!(SupplyEvent { user: ctx.accounts.user.key(), amount, shares: receipt_amount });
         Ok(())
     }
 
-    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+    pub fn withdraw(ctx: Context<Withdraw>, shares: u64) -> Result<()> {
         let market = &mut ctx.accounts.market;
+        require!(shares > 0, LendingError::InvalidAmount);
+
+        accrue_market(market, ctx.accounts.vault.amount)?;
+
+        // Redeem shares at the current exchange rate, rounding down
+        let rate = exchange_rate(market);
+        let amount = to_u64(rate.mul_amount(shares as u128, Rounding::Down)?)?;
         require!(amount > 0, LendingError::InvalidAmount);
+        require!(amount <= ctx.accounts.vault.amount, LendingError::InsufficientLiquidity);
 
         // Burn receipt
         let burn_cpi = Burn {
@@ -131,7 +148,7 @@ pub mod lending {
             from: ctx.accounts.user_receipt_account.to_account_info(),
             authority: ctx.accounts.user.to_account_info(),
         };
-        token::burn(CpiContext::new(ctx.accounts.token_program.to_account_info(), burn_cpi), amount)?;
+        token::burn(CpiContext::new(ctx.accounts.token_program.to_account_info(), burn_cpi), shares)?;
 
         // Transfer base back
         let cpi_accounts = Transfer {
@@ -143,24 +160,432 @@ pub mod lending {
         let signer = &[&seeds[..]];
         token::transfer(CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer), amount)?;
 
-        // Update accounting using index in later iteration; MVP track nominal
-        market.total_supply = market.total_supply.checked_sub(amount as u128).unwrap();
-        emit!(WithdrawEvent { user: ctx.accounts.user.key(), amount });
+        market.total_supply = market.total_supply.checked_sub(shares as u128).ok_or(LendingError::MathOverflow)?;
+        emit!(WithdrawEvent { user: ctx.accounts.user.key(), amount, shares });
+        Ok(())
+    }
+
+    pub fn borrow(ctx: Context<Borrow>, amount: u64, collateral_amount: u64) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        require!(!market.paused, LendingError::Paused);
+        require!(amount > 0, LendingError::InvalidAmount);
+
+        accrue_market(market, ctx.accounts.vault.amount)?;
+
+        let debt = &mut ctx.accounts.debt;
+        if debt.owner == Pubkey::default() {
+            debt.owner = ctx.accounts.user.key();
+            debt.market = market.key();
+            debt.bump = ctx.bumps.debt;
+        }
+
+        // Lock additional receipt tokens as collateral
+        if collateral_amount > 0 {
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.user_receipt_account.to_account_info(),
+                to: ctx.accounts.collateral_vault.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            };
+            token::transfer(CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts), collateral_amount)?;
+            debt.collateral_amount = debt.collateral_amount
+                .checked_add(collateral_amount)
+                .ok_or(LendingError::MathOverflow)?;
+        }
+
+        // Debt after this borrow must stay within the LTV, below the liquidation threshold
+        let new_debt = debt_value(debt.scaled_debt, market.borrow_index)?
+            .checked_add(amount as u128)
+            .ok_or(LendingError::MathOverflow)?;
+        let collateral_value = exchange_rate(market)
+            .mul_amount(debt.collateral_amount as u128, Rounding::Down)?;
+        let max_debt = bps_of(collateral_value, market.ltv_bps, Rounding::Down)?;
+        require!(new_debt <= max_debt, LendingError::InsufficientCollateral);
+        require!(amount <= ctx.accounts.vault.amount, LendingError::InsufficientLiquidity);
+
+        // Transfer base out of the vault (authority = market PDA)
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.user_base_account.to_account_info(),
+            authority: market.to_account_info(),
+        };
+        let seeds = &[b"market", market.base_mint.as_ref(), &[market.bump]];
+        let signer = &[&seeds[..]];
+        token::transfer(CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer), amount)?;
+
+        record_borrow(market, debt, amount)?;
+
+        emit!(BorrowEvent { user: ctx.accounts.user.key(), amount, collateral_amount: debt.collateral_amount });
+        Ok(())
+    }
+
+    /// Set the program-derived signer allowed to borrow without receipt collateral.
+    /// The delegate (e.g. the LVF config PDA) enforces its own collateral rules.
+    pub fn set_credit_delegate(ctx: Context<SetCreditDelegate>, delegate: Pubkey) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        require_keys_eq!(market.authority, ctx.accounts.authority.key(), LendingError::Unauthorized);
+        market.credit_delegate = delegate;
+        Ok(())
+    }
+
+    /// Move accrued protocol reserves to the forge treasury (authority only).
+    pub fn withdraw_reserves(ctx: Context<WithdrawReserves>, amount: u64) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        require!(amount > 0, LendingError::InvalidAmount);
+
+        accrue_market(market, ctx.accounts.vault.amount)?;
+        require!(amount as u128 <= market.total_reserves, LendingError::InsufficientReserves);
+        require!(amount <= ctx.accounts.vault.amount, LendingError::InsufficientLiquidity);
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.treasury.to_account_info(),
+            authority: market.to_account_info(),
+        };
+        let seeds = &[b"market", market.base_mint.as_ref(), &[market.bump]];
+        let signer = &[&seeds[..]];
+        token::transfer(CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer), amount)?;
+
+        market.total_reserves -= amount as u128;
+        emit!(ReservesWithdrawn { market: market.key(), treasury: ctx.accounts.treasury.key(), amount, remaining: market.total_reserves });
+        Ok(())
+    }
+
+    /// Borrow on behalf of `user` under the market's credit delegate.
+    pub fn borrow_delegated(ctx: Context<BorrowDelegated>, amount: u64) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        require!(!market.paused, LendingError::Paused);
+        require!(amount > 0, LendingError::InvalidAmount);
+
+        accrue_market(market, ctx.accounts.vault.amount)?;
+
+        let debt = &mut ctx.accounts.debt;
+        if debt.owner == Pubkey::default() {
+            debt.owner = ctx.accounts.user.key();
+            debt.market = market.key();
+            debt.bump = ctx.bumps.debt;
+        }
+        require!(amount <= ctx.accounts.vault.amount, LendingError::InsufficientLiquidity);
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.user_base_account.to_account_info(),
+            authority: market.to_account_info(),
+        };
+        let seeds = &[b"market", market.base_mint.as_ref(), &[market.bump]];
+        let signer = &[&seeds[..]];
+        token::transfer(CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer), amount)?;
+
+        record_borrow(market, debt, amount)?;
+
+        emit!(BorrowEvent { user: ctx.accounts.user.key(), amount, collateral_amount: debt.collateral_amount });
+        Ok(())
+    }
+
+    /// Write off up to `amount` of a delegated debt its owner can no longer cover.
+    /// Protocol reserves absorb the loss first; the rest lowers the supply index.
+    pub fn write_off_delegated(ctx: Context<WriteOffDelegated>, amount: u64) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        require!(amount > 0, LendingError::InvalidAmount);
+
+        accrue_market(market, ctx.accounts.vault.amount)?;
+
+        let debt = &mut ctx.accounts.debt;
+        let written_off = reduce_debt(market, debt, amount)?;
+
+        let from_reserves = (written_off as u128).min(market.total_reserves);
+        market.total_reserves -= from_reserves;
+        let supplier_loss = written_off as u128 - from_reserves;
+        if supplier_loss > 0 && market.total_supply > 0 {
+            let per_share = supply_index_decrement(supplier_loss, market.total_supply)?;
+            market.supply_index = market.supply_index.saturating_sub(per_share.to_scaled());
+        }
+
+        emit!(DebtWrittenOff {
+            owner: debt.owner,
+            amount: written_off,
+            from_reserves: to_u64(from_reserves)?,
+            supply_index: market.supply_index,
+        });
         Ok(())
     }
 
-    pub fn borrow(_ctx: Context<Borrow>, _amount: u64) -> Result<()> {
-        // Placeholder: LVF will manage collateralization; ensure checks before enabling
-        err!(LendingError::Unimplemented)
+    pub fn repay(ctx: Context<Repay>, amount: u64) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        require!(amount > 0, LendingError::InvalidAmount);
+
+        accrue_market(market, ctx.accounts.vault.amount)?;
+
+        let debt = &mut ctx.accounts.debt;
+        let repay_amount = reduce_debt(market, debt, amount)?;
+
+        // Transfer base back into the vault
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.user_base_account.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        token::transfer(CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts), repay_amount)?;
+
+        // Release the locked collateral once the debt is fully repaid
+        let seeds = &[b"market", market.base_mint.as_ref(), &[market.bump]];
+        let signer = &[&seeds[..]];
+        let mut collateral_released = 0;
+        if debt.scaled_debt == 0 && debt.collateral_amount > 0 {
+            let owner_receipt_account = ctx.accounts.owner_receipt_account
+                .as_ref()
+                .ok_or(LendingError::MissingReceiptAccount)?;
+            collateral_released = debt.collateral_amount;
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.collateral_vault.to_account_info(),
+                to: owner_receipt_account.to_account_info(),
+                authority: market.to_account_info(),
+            };
+            token::transfer(CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer), collateral_released)?;
+            debt.collateral_amount = 0;
+        }
+
+        emit!(RepayEvent { user: ctx.accounts.user.key(), owner: debt.owner, amount: repay_amount, collateral_released });
+        Ok(())
     }
 
-    pub fn repay(_ctx: Context<Repay>, _amount: u64) -> Result<()> {
-        err!(LendingError::Unimplemented)
+    /// Repay part of a debt whose value exceeds the liquidation threshold of its receipt
+    /// collateral, seizing receipts worth the repayment plus the market's liquidation bonus.
+    pub fn liquidate(ctx: Context<Liquidate>, repay_amount: u64) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        require!(repay_amount > 0, LendingError::InvalidAmount);
+
+        accrue_market(market, ctx.accounts.vault.amount)?;
+
+        // Credit-delegated debt carries no receipt collateral; the delegate liquidates it
+        let debt = &mut ctx.accounts.debt;
+        require!(debt.collateral_amount > 0, LendingError::InsufficientCollateral);
+
+        let owed = debt_value(debt.scaled_debt, market.borrow_index)?;
+        let rate = exchange_rate(market);
+        let collateral_value = rate.mul_amount(debt.collateral_amount as u128, Rounding::Down)?;
+        let liquidation_limit = bps_of(collateral_value, market.liquidation_threshold_bps, Rounding::Down)?;
+        require!(owed > liquidation_limit, LendingError::PositionHealthy);
+
+        // At most OBLIGATION_CLOSE_FACTOR_BPS of the debt per call
+        let max_repay = bps_of(owed, OBLIGATION_CLOSE_FACTOR_BPS, Rounding::Up)?;
+        let repaid = reduce_debt(market, debt, (repay_amount as u128).min(max_repay) as u64)?;
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.liquidator_base_account.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.liquidator.to_account_info(),
+        };
+        token::transfer(CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts), repaid)?;
+
+        // Receipts worth the repayment plus the bonus, capped at the locked collateral
+        let seize_value = bps_of(repaid as u128, 10_000 + market.liquidation_bonus_bps, Rounding::Down)?;
+        let seized = to_u64(rate.div_amount(seize_value, Rounding::Down)?.min(debt.collateral_amount as u128))?;
+        debt.collateral_amount -= seized;
+
+        let seeds = &[b"market", market.base_mint.as_ref(), &[market.bump]];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.collateral_vault.to_account_info(),
+            to: ctx.accounts.liquidator_receipt_account.to_account_info(),
+            authority: market.to_account_info(),
+        };
+        token::transfer(CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer), seized)?;
+
+        emit!(LiquidateEvent { liquidator: ctx.accounts.liquidator.key(), owner: debt.owner, repaid, seized });
+        Ok(())
+    }
+
+    pub fn initialize_collateral_config(ctx: Context<InitializeCollateralConfig>, params: CollateralConfigParams) -> Result<()> {
+        obligation::initialize_collateral_config(ctx, params)
+    }
+
+    pub fn initialize_obligation(ctx: Context<InitializeObligation>) -> Result<()> {
+        obligation::initialize_obligation(ctx)
+    }
+
+    pub fn deposit_collateral(ctx: Context<DepositCollateral>, amount: u64) -> Result<()> {
+        obligation::deposit_collateral(ctx, amount)
+    }
+
+    pub fn withdraw_collateral(ctx: Context<WithdrawCollateral>, amount: u64) -> Result<()> {
+        obligation::withdraw_collateral(ctx, amount)
+    }
+
+    pub fn borrow_obligation(ctx: Context<BorrowObligation>, amount: u64) -> Result<()> {
+        obligation::borrow_obligation(ctx, amount)
+    }
+
+    pub fn repay_obligation(ctx: Context<RepayObligation>, amount: u64) -> Result<()> {
+        obligation::repay_obligation(ctx, amount)
+    }
+
+    pub fn liquidate_obligation(ctx: Context<LiquidateObligation>, repay_amount: u64) -> Result<()> {
+        obligation::liquidate_obligation(ctx, repay_amount)
+    }
+}
+
+/// Accrue interest on the borrow and supply indexes up to the current timestamp.
+fn accrue_market(market: &mut Account<Market>, vault_amount: u64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp as u64;
+    if now <= market.last_accrued_ts { return Ok(()); }
+
+    let annual_rate = borrow_rate(market, vault_amount)?;
+
+    // compound the borrow index: index *= e^(annual_rate * elapsed_years)
+    let seconds = now - market.last_accrued_ts;
+    let growth = compound_growth(annual_rate, seconds)?;
+
+    let old_index = market.borrow_index;
+    market.borrow_index = Decimal::from_scaled(old_index)
+        .checked_mul(growth, Rounding::Down)?
+        .to_scaled();
+
+    // outstanding debt grows with the index
+    let old_borrowed = market.total_borrowed;
+    market.total_borrowed = mul_div(old_borrowed, market.borrow_index, old_index, Rounding::Down)?;
+    let interest = market.total_borrowed - old_borrowed;
+
+    // suppliers earn the interest net of the reserve factor
+    let (reserve_share, supplier_interest) = split_interest(interest, market.reserve_factor_bps)?;
+    market.total_reserves = market.total_reserves
+        .checked_add(reserve_share)
+        .ok_or(LendingError::MathOverflow)?;
+    if market.total_supply > 0 {
+        let (per_share, carry) = supply_index_increment(
+            supplier_interest,
+            market.supply_index_carry,
+            market.total_supply,
+        )?;
+        market.supply_index = Decimal::from_scaled(market.supply_index)
+            .checked_add(per_share)?
+            .to_scaled();
+        market.supply_index_carry = carry;
+    }
+    market.last_accrued_ts = now;
+
+    emit!(InterestAccrued {
+        market: market.key(),
+        borrow_rate_bps: annual_rate.to_bps(Rounding::Down)?,
+        borrow_index: market.borrow_index,
+        supply_index: market.supply_index,
+        interest: to_u64(interest)?,
+        reserve_share: to_u64(reserve_share)?,
+    });
+    Ok(())
+}
+
+/// Split accrued interest into the protocol reserve share (rounded up, at most the
+/// whole interest) and the suppliers' remainder.
+pub fn split_interest(interest: u128, reserve_factor_bps: u64) -> Result<(u128, u128)> {
+    let reserve_share = bps_of(interest, reserve_factor_bps, Rounding::Up)?.min(interest);
+    Ok((reserve_share, interest - reserve_share))
+}
+
+/// Supply index increase for `supplier_interest` spread over `total_supply` shares.
+/// Frequent accruals earn less than one index unit per share each, so the division
+/// remainder (in SCALE units) is carried into the next accrual instead of dropped.
+/// Returns the per-share increase and the new carry.
+pub fn supply_index_increment(supplier_interest: u128, carry: u128, total_supply: u128) -> Result<(Decimal, u128)> {
+    let numerator = supplier_interest
+        .checked_mul(forge_math::SCALE)
+        .and_then(|scaled| scaled.checked_add(carry))
+        .ok_or(LendingError::MathOverflow)?;
+    Ok((Decimal::from_scaled(numerator / total_supply), numerator % total_supply))
+}
+
+/// Supply index decrease for a `loss` spread over `total_supply` shares, rounded up so
+/// suppliers never redeem more than the market still holds.
+pub fn supply_index_decrement(loss: u128, total_supply: u128) -> Result<Decimal> {
+    Ok(Decimal::from_ratio(loss, total_supply, Rounding::Up)?)
+}
+
+/// Bounds shared by market creation and parameter updates.
+fn validate_market_params(params: &MarketParams) -> Result<()> {
+    let model = params.interest_model();
+    let MarketParams { ltv_bps, liquidation_threshold_bps, liquidation_bonus_bps, reserve_factor_bps, .. } = *params;
+    require!(model.base_rate_bps <= 1_000_000, LendingError::InvalidParams);
+    require!(model.kink_bps > 0 && model.kink_bps as u128 <= BPS_SCALE, LendingError::InvalidParams);
+    require!(model.slope1_bps <= model.slope2_bps, LendingError::InvalidParams);
+    require!(model.slope2_bps <= 1_000_000, LendingError::InvalidParams);
+    require!(
+        liquidation_threshold_bps > 0 && liquidation_threshold_bps as u128 <= BPS_SCALE,
+        LendingError::InvalidParams
+    );
+    require!(ltv_bps > 0 && ltv_bps < liquidation_threshold_bps, LendingError::InvalidParams);
+    require!(liquidation_bonus_bps <= MAX_LIQUIDATION_BONUS_BPS, LendingError::InvalidParams);
+    require!(reserve_factor_bps as u128 <= BPS_SCALE, LendingError::InvalidParams);
+    Ok(())
+}
+
+/// Annual borrow rate from the kinked utilization model.
+fn borrow_rate(market: &Market, vault_amount: u64) -> Result<Decimal> {
+    // utilization = borrowed / max(1, vault + borrowed - reserves)
+    let supply = (vault_amount as u128)
+        .checked_add(market.total_borrowed)
+        .ok_or(LendingError::MathOverflow)?
+        .saturating_sub(market.total_reserves)
+        .max(1);
+    let util = Decimal::from_ratio(market.total_borrowed, supply, Rounding::Down)?;
+
+    // piecewise interest rate
+    let model = &market.interest_model;
+    let kink = Decimal::from_bps(model.kink_bps);
+    let base = Decimal::from_bps(model.base_rate_bps);
+    let slope1 = Decimal::from_bps(model.slope1_bps);
+    let slope2 = Decimal::from_bps(model.slope2_bps);
+
+    let annual_rate = if util <= kink {
+        base.checked_add(util.checked_mul(slope1, Rounding::Down)?)?
+    } else {
+        let pre = base.checked_add(kink.checked_mul(slope1, Rounding::Down)?)?;
+        let delta = util.checked_sub(kink)?;
+        pre.checked_add(delta.checked_mul(slope2, Rounding::Down)?)?
+    };
+    Ok(annual_rate)
+}
+
+/// Base tokens per receipt share. Receipts are priced only by the supply index, which
+/// grows with the suppliers' share of accrued interest; the vault balance does not
+/// enter the price, so donations to the vault cannot move it.
+fn exchange_rate(market: &Market) -> Decimal {
+    Decimal::from_scaled(market.supply_index)
+}
+
+/// Record debt scaled against the current index (rounded up against the borrower).
+fn record_borrow(market: &mut Market, debt: &mut DebtAccount, amount: u64) -> Result<()> {
+    let scaled = Decimal::from_scaled(market.borrow_index)
+        .div_amount(amount as u128, Rounding::Up)?;
+    debt.scaled_debt = debt.scaled_debt.checked_add(scaled).ok_or(LendingError::MathOverflow)?;
+    market.total_borrowed = market.total_borrowed
+        .checked_add(amount as u128)
+        .ok_or(LendingError::MathOverflow)?;
+    Ok(())
+}
+
+/// Reduce a receipt-collateral debt by up to `amount`; returns the amount repaid.
+fn reduce_debt(market: &mut Market, debt: &mut DebtAccount, amount: u64) -> Result<u64> {
+    let owed = debt_value(debt.scaled_debt, market.borrow_index)?;
+    require!(owed > 0, LendingError::NoDebt);
+    let repaid = (amount as u128).min(owed) as u64;
+
+    if repaid as u128 == owed {
+        debt.scaled_debt = 0;
+    } else {
+        let scaled = Decimal::from_scaled(market.borrow_index)
+            .div_amount(repaid as u128, Rounding::Down)?;
+        debt.scaled_debt = debt.scaled_debt.saturating_sub(scaled);
     }
+    market.total_borrowed = market.total_borrowed.saturating_sub(repaid as u128);
+    Ok(repaid)
+}
+
+/// Current debt owed for a scaled debt balance, rounded up.
+pub fn debt_value(scaled_debt: u128, index: u128) -> Result<u128> {
+    Ok(Decimal::from_scaled(index).mul_amount(scaled_debt, Rounding::Up)?)
 }
 
 #[derive(Accounts)]
-#[instruction(params: InitializeMarketParams)]
+#[instruction(params: MarketParams)]
 pub struct InitializeMarket<'info> {
     #[account(
         init,
@@ -173,6 +598,8 @@ pub struct InitializeMarket<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
     pub base_mint: Account<'info, Mint>,
+    #[account(constraint = price_feed.mint == base_mint.key() @ LendingError::InvalidParams)]
+    pub price_feed: Account<'info, PriceFeed>,
     #[account(
         init,
         payer = authority,
@@ -187,11 +614,28 @@ pub struct InitializeMarket<'info> {
         mint::authority = market
     )]
     pub receipt_mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"collateral", market.key().as_ref()],
+        bump,
+        token::mint = receipt_mint,
+        token::authority = market
+    )]
+    pub collateral_vault: Account<'info, TokenAccount>,
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
+#[derive(Accounts)]
+pub struct UpdateMarketParams<'info> {
+    #[account(mut, has_one = authority, has_one = vault)]
+    pub market: Account<'info, Market>,
+    pub authority: Signer<'info>,
+    pub vault: Account<'info, TokenAccount>,
+}
+
 #[derive(Accounts)]
 pub struct PauseMarket<'info> {
     #[account(mut, has_one = authority)]
@@ -200,14 +644,36 @@ pub struct PauseMarket<'info> {
 }
 
 #[derive(Accounts)]
-pub struct AccrueInterest<'info> {
+pub struct SetCreditDelegate<'info> {
+    #[account(mut, has_one = authority)]
+    pub market: Account<'info, Market>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawReserves<'info> {
+    #[account(mut, has_one = authority, has_one = vault)]
+    pub market: Account<'info, Market>,
+    pub authority: Signer<'info>,
     #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(seeds = [b"forge_protocol"], bump = forge_protocol.bump, seeds::program = forge_core::ID)]
+    pub forge_protocol: Account<'info, ForgeProtocol>,
+    #[account(mut, token::mint = market.base_mint, token::authority = forge_protocol.treasury)]
+    pub treasury: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct AccrueInterest<'info> {
+    #[account(mut, has_one = vault)]
     pub market: Account<'info, Market>,
+    pub vault: Account<'info, TokenAccount>,
 }
 
 #[derive(Accounts)]
 pub struct Supply<'info> {
-    #[account(mut)]
+    #[account(mut, has_one = vault, has_one = receipt_mint)]
     pub market: Account<'info, Market>,
     #[account(mut)]
     pub user: Signer<'info>,
@@ -224,7 +690,7 @@ pub struct Supply<'info> {
 
 #[derive(Accounts)]
 pub struct Withdraw<'info> {
-    #[account(mut)]
+    #[account(mut, has_one = vault, has_one = receipt_mint)]
     pub market: Account<'info, Market>,
     #[account(mut)]
     pub user: Signer<'info>,
@@ -241,24 +707,218 @@ pub struct Withdraw<'info> {
 
 #[derive(Accounts)]
 pub struct Borrow<'info> {
+    #[account(mut, has_one = vault, has_one = receipt_mint)]
+    pub market: Account<'info, Market>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = DebtAccount::SIZE,
+        seeds = [b"debt", market.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub debt: Account<'info, DebtAccount>,
+    #[account(mut)]
+    pub user_base_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    pub receipt_mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub user_receipt_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"collateral", market.key().as_ref()],
+        bump
+    )]
+    pub collateral_vault: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct BorrowDelegated<'info> {
+    #[account(
+        mut,
+        has_one = vault,
+        constraint = market.credit_delegate == delegate.key() @ LendingError::Unauthorized
+    )]
+    pub market: Account<'info, Market>,
+    pub delegate: Signer<'info>,
+    /// Debt owner; signs so only the delegate's own PDAs can be charged
+    pub user: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = DebtAccount::SIZE,
+        seeds = [b"debt", market.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub debt: Account<'info, DebtAccount>,
+    #[account(mut)]
+    pub user_base_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WriteOffDelegated<'info> {
+    #[account(
+        mut,
+        has_one = vault,
+        constraint = market.credit_delegate == delegate.key() @ LendingError::Unauthorized
+    )]
     pub market: Account<'info, Market>,
+    pub delegate: Signer<'info>,
+    /// Debt owner; signs so only the delegate's own PDAs can be written off
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"debt", market.key().as_ref(), user.key().as_ref()],
+        bump = debt.bump,
+        has_one = market
+    )]
+    pub debt: Account<'info, DebtAccount>,
+    pub vault: Account<'info, TokenAccount>,
 }
 
 #[derive(Accounts)]
 pub struct Repay<'info> {
+    #[account(mut, has_one = vault)]
+    pub market: Account<'info, Market>,
+    /// Payer of the repayment; may differ from the debt owner (e.g. a liquidator)
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"debt", market.key().as_ref(), debt.owner.as_ref()],
+        bump = debt.bump,
+        has_one = market
+    )]
+    pub debt: Account<'info, DebtAccount>,
+    #[account(mut)]
+    pub user_base_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    /// Receives released collateral; only required when the repayment clears the debt
+    #[account(mut, constraint = owner_receipt_account.owner == debt.owner @ LendingError::Unauthorized)]
+    pub owner_receipt_account: Option<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"collateral", market.key().as_ref()],
+        bump
+    )]
+    pub collateral_vault: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct Liquidate<'info> {
+    #[account(mut, has_one = vault)]
     pub market: Account<'info, Market>,
+    pub liquidator: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"debt", market.key().as_ref(), debt.owner.as_ref()],
+        bump = debt.bump,
+        has_one = market
+    )]
+    pub debt: Account<'info, DebtAccount>,
+    #[account(mut)]
+    pub liquidator_base_account: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = market.receipt_mint)]
+    pub liquidator_receipt_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"collateral", market.key().as_ref()],
+        bump
+    )]
+    pub collateral_vault: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[event]
+pub struct InterestAccrued {
+    pub market: Pubkey,
+    pub borrow_rate_bps: u64,
+    pub borrow_index: u128,
+    pub supply_index: u128,
+    pub interest: u64,
+    pub reserve_share: u64,
 }
 
 #[event]
 pub struct SupplyEvent {
     pub user: Pubkey,
     pub amount: u64,
+    pub shares: u64,
 }
 
 #[event]
 pub struct WithdrawEvent {
     pub user: Pubkey,
     pub amount: u64,
+    pub shares: u64,
+}
+
+#[event]
+pub struct BorrowEvent {
+    pub user: Pubkey,
+    pub amount: u64,
+    pub collateral_amount: u64,
+}
+
+#[event]
+pub struct RepayEvent {
+    pub user: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub collateral_released: u64,
+}
+
+#[event]
+pub struct LiquidateEvent {
+    pub liquidator: Pubkey,
+    pub owner: Pubkey,
+    pub repaid: u64,
+    pub seized: u64,
+}
+
+#[event]
+pub struct MarketParamsUpdated {
+    pub market: Pubkey,
+    pub old_interest_model: InterestRateModelConfig,
+    pub new_interest_model: InterestRateModelConfig,
+    pub old_ltv_bps: u64,
+    pub new_ltv_bps: u64,
+    pub old_liquidation_threshold_bps: u64,
+    pub new_liquidation_threshold_bps: u64,
+    pub old_liquidation_bonus_bps: u64,
+    pub new_liquidation_bonus_bps: u64,
+    pub old_reserve_factor_bps: u64,
+    pub new_reserve_factor_bps: u64,
+}
+
+#[event]
+pub struct DebtWrittenOff {
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub from_reserves: u64,
+    pub supply_index: u128,
+}
+
+#[event]
+pub struct ReservesWithdrawn {
+    pub market: Pubkey,
+    pub treasury: Pubkey,
+    pub amount: u64,
+    pub remaining: u128,
 }
 
 #[error_code]
@@ -268,15 +928,97 @@ pub enum LendingError {
     #[msg("Invalid amount")] InvalidAmount,
     #[msg("Unauthorized")] Unauthorized,
     #[msg("Unimplemented")] Unimplemented,
+    #[msg("Insufficient collateral")] InsufficientCollateral,
+    #[msg("Insufficient liquidity in vault")] InsufficientLiquidity,
+    #[msg("No outstanding debt")] NoDebt,
+    #[msg("Math overflow")] MathOverflow,
+    #[msg("Receipt account required to release collateral")] MissingReceiptAccount,
+    #[msg("Amount exceeds protocol reserves")] InsufficientReserves,
+    #[msg("Obligation has no free deposit or borrow slot")] ObligationFull,
+    #[msg("Collateral not deposited in this obligation")] CollateralNotFound,
+    #[msg("Obligation would exceed its borrow limit")] ObligationUnhealthy,
+    #[msg("Obligation is not liquidatable")] ObligationHealthy,
+    #[msg("Remaining accounts do not match the obligation")] InvalidRemainingAccounts,
+    #[msg("Debt is within its liquidation threshold")] PositionHealthy,
+    #[msg("Borrow market interest not accrued in this transaction")] StaleMarket,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub struct InitializeMarketParams {
+/// Rate model, collateral terms and reserve factor, used by both
+/// `initialize_market` and `update_market_params`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct MarketParams {
     pub base_rate_bps: u64,
     pub slope1_bps: u64,
     pub slope2_bps: u64,
     pub kink_bps: u64,
+    pub ltv_bps: u64,
     pub liquidation_threshold_bps: u64,
+    pub liquidation_bonus_bps: u64,
+    pub reserve_factor_bps: u64,
 }
 
+impl MarketParams {
+    pub fn interest_model(&self) -> InterestRateModelConfig {
+        InterestRateModelConfig {
+            base_rate_bps: self.base_rate_bps,
+            slope1_bps: self.slope1_bps,
+            slope2_bps: self.slope2_bps,
+            kink_bps: self.kink_bps,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use forge_math::SCALE;
+
+    #[test]
+    fn split_interest_rounds_reserve_share_up() {
+        assert_eq!(split_interest(1_000, 1_000).unwrap(), (100, 900));
+        assert_eq!(split_interest(1_001, 1_000).unwrap(), (101, 900));
+        assert_eq!(split_interest(1, 1).unwrap(), (1, 0));
+        assert_eq!(split_interest(1_000, 0).unwrap(), (0, 1_000));
+        assert_eq!(split_interest(1_000, 10_000).unwrap(), (1_000, 0));
+        assert_eq!(split_interest(0, 2_000).unwrap(), (0, 0));
+    }
+
+    #[test]
+    fn supply_index_increment_spreads_interest_per_share() {
+        let (per_share, carry) = supply_index_increment(50, 0, 100).unwrap();
+        assert_eq!(per_share, Decimal::from_bps(5_000));
+        assert_eq!(carry, 0);
+        assert_eq!(supply_index_increment(0, 0, 100).unwrap(), (Decimal::ZERO, 0));
+    }
+
+    #[test]
+    fn supply_index_increment_carries_remainder() {
+        // 1 unit over 3 * SCALE shares earns 0.33 index units per share; the rest carries
+        let total_supply = 3 * SCALE;
+        let (per_share, carry) = supply_index_increment(1, 0, total_supply).unwrap();
+        assert_eq!(per_share, Decimal::ZERO);
+        assert_eq!(carry, SCALE);
+
+        // the carried remainder completes a unit on the following accruals
+        let (per_share, carry) = supply_index_increment(1, carry, total_supply).unwrap();
+        assert_eq!(per_share, Decimal::ZERO);
+        assert_eq!(carry, 2 * SCALE);
+        let (per_share, carry) = supply_index_increment(1, carry, total_supply).unwrap();
+        assert_eq!(per_share, Decimal::from_scaled(1));
+        assert_eq!(carry, 0);
+    }
 
+    #[test]
+    fn supply_index_increment_overflow() {
+        assert!(supply_index_increment(u128::MAX, 0, 1).is_err());
+        assert!(supply_index_increment(u128::MAX / SCALE, u128::MAX, 1).is_err());
+    }
+
+    #[test]
+    fn supply_index_decrement_rounds_against_suppliers() {
+        assert_eq!(supply_index_decrement(50, 100).unwrap().to_scaled(), SCALE / 2);
+        // a loss smaller than one index unit per share still lowers the index
+        assert_eq!(supply_index_decrement(1, 3 * SCALE).unwrap().to_scaled(), 1);
+        assert_eq!(supply_index_decrement(0, 100).unwrap().to_scaled(), 0);
+    }
+}
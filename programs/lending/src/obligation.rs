@@ -0,0 +1,550 @@
+// Obligations: cross-market borrowing against forge_crucibles cTokens.
+// Collateral is valued as cTokens -> base tokens at the crucible exchange rate, then
+// base tokens -> USD via the oracle; debt is valued in USD from each borrow market's feed.
+// Health checks read every deposit and borrow from `remaining_accounts`:
+//   per deposit (in order): [collateral_config, crucible_vault, ctoken_mint, price_feed]
+//   per borrow  (in order): [market, price_feed]
+// Borrow markets other than the one the instruction accrues itself must already be
+// accrued at the current timestamp (e.g. by `accrue_interest` earlier in the transaction).
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use forge_crucibles::state::{base_for_ctokens, Crucible};
+use forge_math::{bps_of, mul_div, Decimal, Rounding, BPS_SCALE};
+use oracle::state::PriceFeed;
+
+use crate::state::*;
+use crate::{accrue_market, debt_value, LendingError};
+
+/// Max share of one borrow that a single liquidation may repay (50%)
+pub const OBLIGATION_CLOSE_FACTOR_BPS: u64 = 5_000;
+/// Upper bound on the liquidation bonus a collateral config may grant (20%)
+pub const MAX_LIQUIDATION_BONUS_BPS: u64 = 2_000;
+
+/// Register a crucible's cToken as obligation collateral (crucible authority only).
+pub fn initialize_collateral_config(
+    ctx: Context<InitializeCollateralConfig>,
+    params: CollateralConfigParams,
+) -> Result<()> {
+    require!(
+        params.ltv_bps > 0
+            && params.ltv_bps < params.liquidation_threshold_bps
+            && params.liquidation_threshold_bps as u128 <= BPS_SCALE,
+        LendingError::InvalidParams
+    );
+    require!(params.liquidation_bonus_bps <= MAX_LIQUIDATION_BONUS_BPS, LendingError::InvalidParams);
+
+    let crucible = &ctx.accounts.crucible;
+    let config = &mut ctx.accounts.config;
+    config.authority = ctx.accounts.authority.key();
+    config.crucible = crucible.key();
+    config.ctoken_mint = crucible.ctoken_mint;
+    config.crucible_vault = crucible.vault;
+    config.price_feed = crucible.oracle;
+    config.vault = ctx.accounts.vault.key();
+    config.base_decimals = ctx.accounts.base_mint.decimals;
+    config.ltv_bps = params.ltv_bps;
+    config.liquidation_threshold_bps = params.liquidation_threshold_bps;
+    config.liquidation_bonus_bps = params.liquidation_bonus_bps;
+    config.total_deposited = 0;
+    config.bump = ctx.bumps.config;
+    Ok(())
+}
+
+pub fn initialize_obligation(ctx: Context<InitializeObligation>) -> Result<()> {
+    let obligation = &mut ctx.accounts.obligation;
+    obligation.owner = ctx.accounts.owner.key();
+    obligation.deposits = Vec::new();
+    obligation.borrows = Vec::new();
+    obligation.bump = ctx.bumps.obligation;
+    Ok(())
+}
+
+/// Lock cTokens as obligation collateral.
+pub fn deposit_collateral(ctx: Context<DepositCollateral>, amount: u64) -> Result<()> {
+    require!(amount > 0, LendingError::InvalidAmount);
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.owner_ctoken_account.to_account_info(),
+        to: ctx.accounts.collateral_vault.to_account_info(),
+        authority: ctx.accounts.owner.to_account_info(),
+    };
+    token::transfer(CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts), amount)?;
+
+    let config_key = ctx.accounts.config.key();
+    let obligation = &mut ctx.accounts.obligation;
+    match obligation.deposits.iter_mut().find(|d| d.config == config_key) {
+        Some(deposit) => {
+            deposit.amount = deposit.amount.checked_add(amount).ok_or(LendingError::MathOverflow)?;
+        }
+        None => {
+            require!(obligation.deposits.len() < MAX_OBLIGATION_DEPOSITS, LendingError::ObligationFull);
+            obligation.deposits.push(ObligationCollateral { config: config_key, amount });
+        }
+    }
+
+    let config = &mut ctx.accounts.config;
+    config.total_deposited = config.total_deposited.checked_add(amount).ok_or(LendingError::MathOverflow)?;
+
+    emit!(CollateralDeposited { obligation: obligation.key(), config: config_key, amount });
+    Ok(())
+}
+
+/// Release cTokens; any remaining borrows must stay within the LTV limit.
+pub fn withdraw_collateral(ctx: Context<WithdrawCollateral>, amount: u64) -> Result<()> {
+    require!(amount > 0, LendingError::InvalidAmount);
+
+    let config_key = ctx.accounts.config.key();
+    let obligation = &mut ctx.accounts.obligation;
+    let index = obligation.deposits
+        .iter()
+        .position(|d| d.config == config_key)
+        .ok_or(LendingError::CollateralNotFound)?;
+    let deposit = &mut obligation.deposits[index];
+    deposit.amount = deposit.amount.checked_sub(amount).ok_or(LendingError::InvalidAmount)?;
+    if deposit.amount == 0 {
+        obligation.deposits.remove(index);
+    }
+
+    if !obligation.borrows.is_empty() {
+        let health = obligation_health(obligation, ctx.remaining_accounts, None)?;
+        require!(health.debt_usd <= health.borrow_limit_usd, LendingError::ObligationUnhealthy);
+    }
+
+    let config = &mut ctx.accounts.config;
+    config.total_deposited = config.total_deposited.saturating_sub(amount);
+    let seeds = &[b"collateral_config", config.ctoken_mint.as_ref(), &[config.bump]];
+    let signer = &[&seeds[..]];
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.collateral_vault.to_account_info(),
+        to: ctx.accounts.owner_ctoken_account.to_account_info(),
+        authority: config.to_account_info(),
+    };
+    token::transfer(CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer), amount)?;
+
+    emit!(CollateralWithdrawn { obligation: obligation.key(), config: config_key, amount });
+    Ok(())
+}
+
+/// Borrow from any market against the obligation's cToken collateral.
+pub fn borrow_obligation(ctx: Context<BorrowObligation>, amount: u64) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    require!(!market.paused, LendingError::Paused);
+    require!(amount > 0, LendingError::InvalidAmount);
+    require!(amount <= ctx.accounts.vault.amount, LendingError::InsufficientLiquidity);
+
+    accrue_market(market, ctx.accounts.vault.amount)?;
+
+    let market_key = market.key();
+    let obligation = &mut ctx.accounts.obligation;
+    let scaled = Decimal::from_scaled(market.borrow_index).div_amount(amount as u128, Rounding::Up)?;
+    match obligation.borrows.iter_mut().find(|b| b.market == market_key) {
+        Some(borrow) => {
+            borrow.scaled_debt = borrow.scaled_debt.checked_add(scaled).ok_or(LendingError::MathOverflow)?;
+        }
+        None => {
+            require!(obligation.borrows.len() < MAX_OBLIGATION_BORROWS, LendingError::ObligationFull);
+            obligation.borrows.push(ObligationBorrow { market: market_key, scaled_debt: scaled });
+        }
+    }
+    market.total_borrowed = market.total_borrowed
+        .checked_add(amount as u128)
+        .ok_or(LendingError::MathOverflow)?;
+
+    let health = obligation_health(obligation, ctx.remaining_accounts, Some(&**market))?;
+    require!(health.debt_usd <= health.borrow_limit_usd, LendingError::ObligationUnhealthy);
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.vault.to_account_info(),
+        to: ctx.accounts.owner_base_account.to_account_info(),
+        authority: market.to_account_info(),
+    };
+    let seeds = &[b"market", market.base_mint.as_ref(), &[market.bump]];
+    let signer = &[&seeds[..]];
+    token::transfer(CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer), amount)?;
+
+    emit!(ObligationBorrowed { obligation: obligation.key(), market: market_key, amount });
+    Ok(())
+}
+
+/// Repay an obligation borrow; anyone may pay.
+pub fn repay_obligation(ctx: Context<RepayObligation>, amount: u64) -> Result<()> {
+    require!(amount > 0, LendingError::InvalidAmount);
+    let market = &mut ctx.accounts.market;
+    accrue_market(market, ctx.accounts.vault.amount)?;
+
+    let repaid = apply_repay(market, &mut ctx.accounts.obligation, amount)?;
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.payer_base_account.to_account_info(),
+        to: ctx.accounts.vault.to_account_info(),
+        authority: ctx.accounts.payer.to_account_info(),
+    };
+    token::transfer(CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts), repaid)?;
+
+    emit!(ObligationRepaid {
+        obligation: ctx.accounts.obligation.key(),
+        market: market.key(),
+        payer: ctx.accounts.payer.key(),
+        amount: repaid,
+    });
+    Ok(())
+}
+
+/// Repay part of an unhealthy obligation's borrow and seize discounted cToken collateral.
+pub fn liquidate_obligation(ctx: Context<LiquidateObligation>, repay_amount: u64) -> Result<()> {
+    require!(repay_amount > 0, LendingError::InvalidAmount);
+    let market = &mut ctx.accounts.market;
+    accrue_market(market, ctx.accounts.vault.amount)?;
+
+    let health = obligation_health(&ctx.accounts.obligation, ctx.remaining_accounts, Some(&**market))?;
+    require!(health.debt_usd > health.liquidation_limit_usd, LendingError::ObligationHealthy);
+
+    // At most OBLIGATION_CLOSE_FACTOR_BPS of this borrow per call
+    let market_key = market.key();
+    let borrow = ctx.accounts.obligation.borrows
+        .iter()
+        .find(|b| b.market == market_key)
+        .ok_or(LendingError::NoDebt)?;
+    let owed = debt_value(borrow.scaled_debt, market.borrow_index)?;
+    let max_repay = bps_of(owed, OBLIGATION_CLOSE_FACTOR_BPS, Rounding::Up)?;
+    let repay_amount = (repay_amount as u128).min(max_repay) as u64;
+    let repaid = apply_repay(market, &mut ctx.accounts.obligation, repay_amount)?;
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.liquidator_base_account.to_account_info(),
+        to: ctx.accounts.vault.to_account_info(),
+        authority: ctx.accounts.liquidator.to_account_info(),
+    };
+    token::transfer(CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts), repaid)?;
+
+    // Seize repaid value plus the bonus, priced against the deposit's current USD value
+    let debt_price = oracle::get_price_default(&ctx.accounts.market_price_feed)?;
+    let repaid_usd = oracle::usd_value(repaid as u128, market.decimals, &debt_price)?;
+    let config = &ctx.accounts.config;
+    let seize_usd = bps_of(repaid_usd as u128, 10_000 + config.liquidation_bonus_bps, Rounding::Down)?;
+
+    let config_key = config.key();
+    let obligation = &mut ctx.accounts.obligation;
+    let index = obligation.deposits
+        .iter()
+        .position(|d| d.config == config_key)
+        .ok_or(LendingError::CollateralNotFound)?;
+    let deposit_amount = obligation.deposits[index].amount;
+    let deposit_usd = collateral_usd(
+        config,
+        deposit_amount,
+        &ctx.accounts.crucible_vault,
+        &ctx.accounts.ctoken_mint,
+        &ctx.accounts.collateral_price_feed,
+    )?;
+    let seized = if deposit_usd == 0 {
+        deposit_amount
+    } else {
+        (mul_div(deposit_amount as u128, seize_usd, deposit_usd, Rounding::Down)?).min(deposit_amount as u128) as u64
+    };
+
+    obligation.deposits[index].amount -= seized;
+    if obligation.deposits[index].amount == 0 {
+        obligation.deposits.remove(index);
+    }
+
+    let config = &mut ctx.accounts.config;
+    config.total_deposited = config.total_deposited.saturating_sub(seized);
+    let seeds = &[b"collateral_config", config.ctoken_mint.as_ref(), &[config.bump]];
+    let signer = &[&seeds[..]];
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.collateral_vault.to_account_info(),
+        to: ctx.accounts.liquidator_ctoken_account.to_account_info(),
+        authority: config.to_account_info(),
+    };
+    token::transfer(CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer), seized)?;
+
+    emit!(ObligationLiquidated {
+        obligation: obligation.key(),
+        market: market_key,
+        config: config_key,
+        liquidator: ctx.accounts.liquidator.key(),
+        repaid,
+        seized,
+    });
+    Ok(())
+}
+
+/// Reduce the obligation's borrow in `market` by up to `amount`; returns the amount repaid.
+fn apply_repay(market: &mut Account<Market>, obligation: &mut Obligation, amount: u64) -> Result<u64> {
+    let market_key = market.key();
+    let index = obligation.borrows
+        .iter()
+        .position(|b| b.market == market_key)
+        .ok_or(LendingError::NoDebt)?;
+    let borrow = &mut obligation.borrows[index];
+    let owed = debt_value(borrow.scaled_debt, market.borrow_index)?;
+    require!(owed > 0, LendingError::NoDebt);
+    let repaid = (amount as u128).min(owed) as u64;
+
+    if repaid as u128 == owed {
+        obligation.borrows.remove(index);
+    } else {
+        let scaled = Decimal::from_scaled(market.borrow_index).div_amount(repaid as u128, Rounding::Down)?;
+        borrow.scaled_debt = borrow.scaled_debt.saturating_sub(scaled);
+    }
+    market.total_borrowed = market.total_borrowed.saturating_sub(repaid as u128);
+    Ok(repaid)
+}
+
+/// USD values of an obligation, scaled by oracle::USD_DECIMALS.
+pub struct ObligationHealth {
+    /// Σ collateral value × LTV: the most that may be borrowed
+    pub borrow_limit_usd: u128,
+    /// Σ collateral value × liquidation threshold: debt above this can be liquidated
+    pub liquidation_limit_usd: u128,
+    pub debt_usd: u128,
+}
+
+/// Value every deposit and borrow of `obligation` from `remaining` (see module docs).
+/// `current` overrides the stored copy of a market already loaded (and accrued) mutably;
+/// every other borrow market must have been accrued at the current timestamp.
+pub fn obligation_health(
+    obligation: &Obligation,
+    remaining: &[AccountInfo],
+    current: Option<&Account<Market>>,
+) -> Result<ObligationHealth> {
+    let deposits = obligation.deposits.len();
+    require!(
+        remaining.len() == deposits * 4 + obligation.borrows.len() * 2,
+        LendingError::InvalidRemainingAccounts
+    );
+
+    let now = Clock::get()?.unix_timestamp as u64;
+    let mut health = ObligationHealth { borrow_limit_usd: 0, liquidation_limit_usd: 0, debt_usd: 0 };
+    for (i, deposit) in obligation.deposits.iter().enumerate() {
+        let accounts = &remaining[i * 4..i * 4 + 4];
+        let config: CollateralConfig = load_account(&accounts[0], &deposit.config)?;
+        let crucible_vault: TokenAccount = load_account(&accounts[1], &config.crucible_vault)?;
+        let ctoken_mint: Mint = load_account(&accounts[2], &config.ctoken_mint)?;
+        let price_feed: PriceFeed = load_account(&accounts[3], &config.price_feed)?;
+
+        let value = collateral_usd(&config, deposit.amount, &crucible_vault, &ctoken_mint, &price_feed)?;
+        health.borrow_limit_usd += bps_of(value, config.ltv_bps, Rounding::Down)?;
+        health.liquidation_limit_usd += bps_of(value, config.liquidation_threshold_bps, Rounding::Down)?;
+    }
+
+    for (i, borrow) in obligation.borrows.iter().enumerate() {
+        let offset = deposits * 4 + i * 2;
+        let loaded: Market;
+        let market: &Market = match current {
+            Some(m) if m.key() == borrow.market => {
+                require_keys_eq!(remaining[offset].key(), borrow.market, LendingError::InvalidRemainingAccounts);
+                m
+            }
+            _ => {
+                loaded = load_account(&remaining[offset], &borrow.market)?;
+                require!(loaded.last_accrued_ts == now, LendingError::StaleMarket);
+                &loaded
+            }
+        };
+        let price_feed: PriceFeed = load_account(&remaining[offset + 1], &market.price_feed)?;
+        let price = oracle::get_price_default(&price_feed)?;
+        let debt = debt_value(borrow.scaled_debt, market.borrow_index)?;
+        health.debt_usd += oracle::usd_value(debt, market.decimals, &price)? as u128;
+    }
+    Ok(health)
+}
+
+/// USD value of `ctokens` of a collateral: cTokens -> base at the crucible rate -> USD.
+fn collateral_usd(
+    config: &CollateralConfig,
+    ctokens: u64,
+    crucible_vault: &TokenAccount,
+    ctoken_mint: &Mint,
+    price_feed: &PriceFeed,
+) -> Result<u128> {
+    let base = base_for_ctokens(ctokens, crucible_vault.amount, ctoken_mint.supply)?;
+    let price = oracle::get_price_default(price_feed)?;
+    Ok(oracle::usd_value(base as u128, config.base_decimals, &price)? as u128)
+}
+
+/// Deserialize a remaining account after checking its address and owning program.
+fn load_account<T: AccountDeserialize + Owner>(info: &AccountInfo, expected: &Pubkey) -> Result<T> {
+    require_keys_eq!(info.key(), *expected, LendingError::InvalidRemainingAccounts);
+    require_keys_eq!(*info.owner, T::owner(), LendingError::InvalidRemainingAccounts);
+    let data = info.try_borrow_data()?;
+    T::try_deserialize(&mut &data[..])
+}
+
+#[derive(Accounts)]
+pub struct InitializeCollateralConfig<'info> {
+    #[account(mut, address = crucible.authority @ LendingError::Unauthorized)]
+    pub authority: Signer<'info>,
+    pub crucible: Box<Account<'info, Crucible>>,
+    #[account(address = crucible.base_mint)]
+    pub base_mint: Box<Account<'info, Mint>>,
+    #[account(address = crucible.ctoken_mint)]
+    pub ctoken_mint: Box<Account<'info, Mint>>,
+    #[account(
+        init,
+        payer = authority,
+        space = CollateralConfig::SIZE,
+        seeds = [b"collateral_config", ctoken_mint.key().as_ref()],
+        bump
+    )]
+    pub config: Box<Account<'info, CollateralConfig>>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"ctoken_collateral", config.key().as_ref()],
+        bump,
+        token::mint = ctoken_mint,
+        token::authority = config
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeObligation<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        init,
+        payer = owner,
+        space = Obligation::SIZE,
+        seeds = [b"obligation", owner.key().as_ref()],
+        bump
+    )]
+    pub obligation: Account<'info, Obligation>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DepositCollateral<'info> {
+    pub owner: Signer<'info>,
+    #[account(mut, has_one = owner, seeds = [b"obligation", owner.key().as_ref()], bump = obligation.bump)]
+    pub obligation: Account<'info, Obligation>,
+    #[account(mut)]
+    pub config: Account<'info, CollateralConfig>,
+    #[account(mut, address = config.vault)]
+    pub collateral_vault: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = config.ctoken_mint)]
+    pub owner_ctoken_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawCollateral<'info> {
+    pub owner: Signer<'info>,
+    #[account(mut, has_one = owner, seeds = [b"obligation", owner.key().as_ref()], bump = obligation.bump)]
+    pub obligation: Account<'info, Obligation>,
+    #[account(mut)]
+    pub config: Account<'info, CollateralConfig>,
+    #[account(mut, address = config.vault)]
+    pub collateral_vault: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = config.ctoken_mint)]
+    pub owner_ctoken_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct BorrowObligation<'info> {
+    pub owner: Signer<'info>,
+    #[account(mut, has_one = owner, seeds = [b"obligation", owner.key().as_ref()], bump = obligation.bump)]
+    pub obligation: Box<Account<'info, Obligation>>,
+    #[account(mut, has_one = vault)]
+    pub market: Box<Account<'info, Market>>,
+    #[account(mut)]
+    pub vault: Box<Account<'info, TokenAccount>>,
+    #[account(mut, token::mint = market.base_mint)]
+    pub owner_base_account: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RepayObligation<'info> {
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub obligation: Box<Account<'info, Obligation>>,
+    #[account(mut, has_one = vault)]
+    pub market: Box<Account<'info, Market>>,
+    #[account(mut)]
+    pub vault: Box<Account<'info, TokenAccount>>,
+    #[account(mut, token::mint = market.base_mint)]
+    pub payer_base_account: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct LiquidateObligation<'info> {
+    pub liquidator: Signer<'info>,
+    #[account(mut)]
+    pub obligation: Box<Account<'info, Obligation>>,
+    #[account(mut, has_one = vault)]
+    pub market: Box<Account<'info, Market>>,
+    #[account(mut)]
+    pub vault: Box<Account<'info, TokenAccount>>,
+    #[account(address = market.price_feed)]
+    pub market_price_feed: Box<Account<'info, PriceFeed>>,
+    #[account(mut)]
+    pub config: Box<Account<'info, CollateralConfig>>,
+    #[account(mut, address = config.vault)]
+    pub collateral_vault: Box<Account<'info, TokenAccount>>,
+    #[account(address = config.crucible_vault)]
+    pub crucible_vault: Box<Account<'info, TokenAccount>>,
+    #[account(address = config.ctoken_mint)]
+    pub ctoken_mint: Box<Account<'info, Mint>>,
+    #[account(address = config.price_feed)]
+    pub collateral_price_feed: Box<Account<'info, PriceFeed>>,
+    #[account(mut, token::mint = market.base_mint)]
+    pub liquidator_base_account: Box<Account<'info, TokenAccount>>,
+    #[account(mut, token::mint = config.ctoken_mint)]
+    pub liquidator_ctoken_account: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CollateralConfigParams {
+    pub ltv_bps: u64,
+    pub liquidation_threshold_bps: u64,
+    pub liquidation_bonus_bps: u64,
+}
+
+#[event]
+pub struct CollateralDeposited {
+    pub obligation: Pubkey,
+    pub config: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct CollateralWithdrawn {
+    pub obligation: Pubkey,
+    pub config: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ObligationBorrowed {
+    pub obligation: Pubkey,
+    pub market: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ObligationRepaid {
+    pub obligation: Pubkey,
+    pub market: Pubkey,
+    pub payer: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ObligationLiquidated {
+    pub obligation: Pubkey,
+    pub market: Pubkey,
+    pub config: Pubkey,
+    pub liquidator: Pubkey,
+    pub repaid: u64,
+    pub seized: u64,
+}
@@ -6,13 +6,22 @@ pub struct Market {
     pub base_mint: Pubkey,
     pub vault: Pubkey,
     pub receipt_mint: Pubkey,
-    pub total_supply: u128,
+    pub total_supply: u128, // receipt shares outstanding
     pub total_borrowed: u128,
-    pub accumulated_index: u128,
+    pub borrow_index: u128, // debt per scaled debt unit, compounds with the borrow rate
+    pub supply_index: u128, // base tokens per receipt share, grows with the suppliers' interest
+    pub supply_index_carry: u128, // supplier interest (x SCALE) not yet spread over the shares
+    pub reserve_factor_bps: u64, // share of borrower interest withheld from suppliers
+    pub total_reserves: u128, // protocol share of accrued interest, withdrawable to the treasury
     pub last_accrued_ts: u64,
     pub interest_model: InterestRateModelConfig,
-    pub liquidation_threshold_bps: u64,
+    pub ltv_bps: u64,                   // max debt per unit of receipt collateral value at borrow time
+    pub liquidation_threshold_bps: u64, // debt/collateral value at which liquidation opens
+    pub liquidation_bonus_bps: u64,     // extra collateral paid to liquidators
     pub paused: bool,
+    pub credit_delegate: Pubkey, // PDA allowed to borrow via borrow_delegated
+    pub price_feed: Pubkey,      // oracle feed for base_mint
+    pub decimals: u8,            // base_mint decimals, for USD valuation
     pub bump: u8,
 }
 
@@ -24,11 +33,20 @@ impl Market {
         32 + // receipt_mint
         16 + // total_supply
         16 + // total_borrowed
-        16 + // accumulated_index
+        16 + // borrow_index
+        16 + // supply_index
+        16 + // supply_index_carry
+        8 +  // reserve_factor_bps
+        16 + // total_reserves
         8 +  // last_accrued_ts
         InterestRateModelConfig::SIZE +
+        8 +  // ltv_bps
         8 +  // liquidation_threshold_bps
+        8 +  // liquidation_bonus_bps
         1 +  // paused
+        32 + // credit_delegate
+        32 + // price_feed
+        1 +  // decimals
         1;   // bump
 }
 
@@ -44,4 +62,83 @@ impl InterestRateModelConfig {
     pub const SIZE: usize = 8 * 4;
 }
 
+#[account]
+pub struct DebtAccount {
+    pub owner: Pubkey,
+    pub market: Pubkey,
+    pub collateral_amount: u64, // receipt tokens locked in the collateral vault
+    pub scaled_debt: u128,      // debt / borrow_index, in forge_math::SCALE units
+    pub bump: u8,
+}
+
+impl DebtAccount {
+    pub const SIZE: usize = 8 + // discriminator
+        32 + // owner
+        32 + // market
+        8 +  // collateral_amount
+        16 + // scaled_debt
+        1;   // bump
+}
+
+/// Max distinct cToken collaterals per obligation
+pub const MAX_OBLIGATION_DEPOSITS: usize = 4;
+/// Max distinct borrow markets per obligation
+pub const MAX_OBLIGATION_BORROWS: usize = 4;
+
+/// Lending terms for one crucible's cToken used as obligation collateral.
+/// Deposited cTokens sit in `vault`, owned by this config PDA.
+#[account]
+pub struct CollateralConfig {
+    pub authority: Pubkey,
+    pub crucible: Pubkey,
+    pub ctoken_mint: Pubkey,
+    pub crucible_vault: Pubkey, // crucible base-token vault backing the cToken rate
+    pub price_feed: Pubkey,     // oracle feed for the crucible base mint
+    pub vault: Pubkey,
+    pub base_decimals: u8,
+    pub ltv_bps: u64,                   // max borrow value per unit of collateral value
+    pub liquidation_threshold_bps: u64, // debt/collateral value at which liquidation opens
+    pub liquidation_bonus_bps: u64,     // extra collateral paid to liquidators
+    pub total_deposited: u64,
+    pub bump: u8,
+}
+
+impl CollateralConfig {
+    pub const SIZE: usize = 8 + // discriminator
+        32 * 6 + // authority, crucible, ctoken_mint, crucible_vault, price_feed, vault
+        1 +  // base_decimals
+        8 +  // ltv_bps
+        8 +  // liquidation_threshold_bps
+        8 +  // liquidation_bonus_bps
+        8 +  // total_deposited
+        1;   // bump
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct ObligationCollateral {
+    pub config: Pubkey,
+    pub amount: u64, // cTokens deposited
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct ObligationBorrow {
+    pub market: Pubkey,
+    pub scaled_debt: u128, // debt / market.borrow_index
+}
+
+/// A user's cross-market position: cToken collateral backing borrows in any market.
+#[account]
+pub struct Obligation {
+    pub owner: Pubkey,
+    pub deposits: Vec<ObligationCollateral>,
+    pub borrows: Vec<ObligationBorrow>,
+    pub bump: u8,
+}
 
+impl Obligation {
+    pub const SIZE: usize = 8 + // discriminator
+        32 + // owner
+        4 + MAX_OBLIGATION_DEPOSITS * (32 + 8) + // deposits
+        4 + MAX_OBLIGATION_BORROWS * (32 + 16) + // borrows
+        1;   // bump
+}
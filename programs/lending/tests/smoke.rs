@@ -1,4 +1,5 @@
 // Basic test placeholders for lending program
+#![allow(unused_imports, clippy::assertions_on_constants)]
 use anchor_lang::prelude::*;
 
 #[test]
@@ -1,16 +1,27 @@
 // Summary: LVF program to open/close leveraged positions using cTokens as collateral
-// and interacting with the lending market. Includes pause, health check stubs,
-// liquidation entry point and admin config.
+// and interacting with the lending market. Includes pause, on-chain health check,
+// permissionless partial liquidation and admin config.
 
 use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token::{self, Token, TokenAccount, Mint, Transfer};
+use forge_crucibles::program::ForgeCrucibles;
+use forge_crucibles::state::{Crucible, FEE_RATE_SCALE, VIRTUAL_ASSETS, VIRTUAL_SHARES};
+use forge_math::{bps_of, mul_div, Decimal, Rounding, BPS_SCALE};
+use lending::program::Lending;
+use lending::state::{DebtAccount, Market};
+use oracle::state::PriceFeed;
 
 pub mod state;
 use state::*;
 
 declare_id!("LvF1111111111111111111111111111111111111111");
 
-pub const RATE_SCALE: u128 = 1_000_000_000u128; // align with crucibles/lending
+/// Max share of a position's debt that a single liquidation may repay (50%)
+pub const CLOSE_FACTOR_BPS: u64 = 5_000;
+/// Debt at or below 1/LIQUIDATION_DUST_DIVISOR of a whole base token may be repaid in
+/// full by a liquidation, so the close factor cannot strand an unliquidatable remainder
+pub const LIQUIDATION_DUST_DIVISOR: u128 = 1_000;
 
 #[program]
 pub mod lvf {
@@ -33,22 +44,420 @@ pub mod lvf {
         Ok(())
     }
 
-    pub fn open_position(_ctx: Context<OpenPosition>, _params: OpenPositionParams) -> Result<()> {
-        // Placeholder; full CPI to crucibles and lending to be added in next iteration
-        err!(LvfError::Unimplemented)
+    /// Lock the owner's cTokens, borrow base from the lending market (the config PDA must
+    /// be the market's credit delegate) and deposit the loan into the crucible.
+    pub fn open_position(ctx: Context<OpenPosition>, params: OpenPositionParams) -> Result<()> {
+        let cfg = &ctx.accounts.config;
+        require!(!cfg.paused, LvfError::Paused);
+        require!(params.collateral_amount > 0, LvfError::InvalidAmount);
+        require!(
+            params.leverage_bps > 10_000 && params.leverage_bps <= cfg.max_leverage_bps,
+            LvfError::InvalidLeverage
+        );
+
+        // Size the loan from the collateral's base value at the current cToken rate
+        let rate = ctoken_exchange_rate(ctx.accounts.crucible_vault.amount, ctx.accounts.ctoken_mint.supply)?;
+        let collateral_value = shares_value(params.collateral_amount as u128, rate)?;
+        let borrow_amount = bps_of(collateral_value, params.leverage_bps - 10_000, Rounding::Down)?;
+        let borrow_amount = u64::try_from(borrow_amount).map_err(|_| LvfError::MathOverflow)?;
+        require!(borrow_amount > 0, LvfError::InvalidAmount);
+
+        // Lock the owner's cTokens in the position vault
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.owner_ctoken_account.to_account_info(),
+            to: ctx.accounts.position_vault.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        token::transfer(CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts), params.collateral_amount)?;
+
+        let owner = ctx.accounts.owner.key();
+        let crucible = ctx.accounts.crucible.key();
+        let position_bump = ctx.bumps.position;
+        let position_seeds = &[b"lvf_position", owner.as_ref(), crucible.as_ref(), &[position_bump]];
+        let config_seeds = &[b"lvf_config".as_ref(), &[cfg.bump]];
+
+        // Borrow base into the position's base account
+        lending::cpi::borrow_delegated(
+            CpiContext::new_with_signer(
+                ctx.accounts.lending_program.to_account_info(),
+                lending::cpi::accounts::BorrowDelegated {
+                    market: ctx.accounts.lending_market.to_account_info(),
+                    delegate: ctx.accounts.config.to_account_info(),
+                    user: ctx.accounts.position.to_account_info(),
+                    payer: ctx.accounts.owner.to_account_info(),
+                    debt: ctx.accounts.debt.to_account_info(),
+                    user_base_account: ctx.accounts.position_base_account.to_account_info(),
+                    vault: ctx.accounts.lending_vault.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                },
+                &[&config_seeds[..], &position_seeds[..]],
+            ),
+            borrow_amount,
+        )?;
+
+        // Deposit the loan into the crucible, minting cTokens to the position vault
+        forge_crucibles::cpi::mint_ctoken(
+            CpiContext::new_with_signer(
+                ctx.accounts.crucibles_program.to_account_info(),
+                forge_crucibles::cpi::accounts::MintCToken {
+                    user: ctx.accounts.position.to_account_info(),
+                    crucible: ctx.accounts.crucible.to_account_info(),
+                    base_mint: ctx.accounts.base_mint.to_account_info(),
+                    ctoken_mint: ctx.accounts.ctoken_mint.to_account_info(),
+                    user_token_account: ctx.accounts.position_base_account.to_account_info(),
+                    user_ctoken_account: ctx.accounts.position_vault.to_account_info(),
+                    vault: ctx.accounts.crucible_vault.to_account_info(),
+                    crucible_authority: ctx.accounts.crucible_authority.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                    associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    rent: ctx.accounts.rent.to_account_info(),
+                    forge_protocol: ctx.accounts.forge_protocol.as_ref().map(|p| p.to_account_info()),
+                    fee_vault: ctx.accounts.fee_vault.as_ref().map(|v| v.to_account_info()),
+                },
+                &[&position_seeds[..]],
+            ),
+            borrow_amount,
+            params.min_ctokens_out,
+        )?;
+
+        ctx.accounts.position_vault.reload()?;
+        ctx.accounts.crucible_vault.reload()?;
+        ctx.accounts.ctoken_mint.reload()?;
+        let entry_rate = ctoken_exchange_rate(ctx.accounts.crucible_vault.amount, ctx.accounts.ctoken_mint.supply)?;
+        let shares = ctx.accounts.position_vault.amount as u128;
+
+        // A freshly opened position must not be liquidatable
+        let ltv = ltv_bps(borrow_amount as u128, shares_value(shares, entry_rate)?)?;
+        require!(ltv < cfg.liquidation_threshold_bps, LvfError::InvalidLeverage);
+
+        let position = &mut ctx.accounts.position;
+        position.owner = owner;
+        position.crucible = crucible;
+        position.p_token_mint = ctx.accounts.ctoken_mint.key();
+        position.pair_market = ctx.accounts.lending_market.key();
+        position.lp_pool = Pubkey::default();
+        position.position_shares = shares;
+        position.borrowed_amount = borrow_amount as u128;
+        position.entry_exchange_rate = entry_rate.to_scaled();
+        position.leverage_bps = params.leverage_bps;
+        position.bump = position_bump;
+
+        emit!(PositionOpened {
+            position: position.key(),
+            owner,
+            crucible,
+            collateral_amount: params.collateral_amount,
+            borrowed_amount: borrow_amount,
+            position_shares: shares as u64,
+            leverage_bps: params.leverage_bps,
+        });
+        Ok(())
     }
 
-    pub fn close_position(_ctx: Context<ClosePosition>) -> Result<()> {
-        err!(LvfError::Unimplemented)
+    /// Unwind `close_bps` of a position: redeem enough cTokens to repay the same share of
+    /// the debt and hand the rest of that slice back to the owner as cTokens. A full close
+    /// (10_000 bps) also closes the position accounts.
+    pub fn close_position(ctx: Context<ClosePosition>, close_bps: u64) -> Result<()> {
+        require!(close_bps > 0 && close_bps <= 10_000, LvfError::InvalidAmount);
+
+        lending::cpi::accrue_interest(CpiContext::new(
+            ctx.accounts.lending_program.to_account_info(),
+            lending::cpi::accounts::AccrueInterest {
+                market: ctx.accounts.lending_market.to_account_info(),
+                vault: ctx.accounts.lending_vault.to_account_info(),
+            },
+        ))?;
+        ctx.accounts.lending_market.reload()?;
+
+        let position = &ctx.accounts.position;
+        let rate = ctoken_exchange_rate(ctx.accounts.crucible_vault.amount, ctx.accounts.ctoken_mint.supply)?;
+        let debt = debt_value(&ctx.accounts.debt, &ctx.accounts.lending_market)?;
+        let full_close = close_bps == 10_000;
+
+        let shares_to_close = if full_close {
+            position.position_shares
+        } else {
+            bps_of(position.position_shares, close_bps, Rounding::Down)?
+        };
+        let debt_to_repay = if full_close {
+            debt
+        } else {
+            bps_of(debt, close_bps, Rounding::Down)?
+        };
+
+        // cTokens needed to cover the repayment after the crucible withdrawal fee, rounded up
+        let gross_debt = gross_of_fee(debt_to_repay, ctx.accounts.crucible.fee_rate, Rounding::Up)?;
+        let shares_for_debt = rate.div_amount(gross_debt, Rounding::Up)?;
+        require!(shares_for_debt <= shares_to_close, LvfError::PositionUnderwater);
+
+        let owner = position.owner;
+        let crucible = position.crucible;
+        let position_seeds = &[b"lvf_position", owner.as_ref(), crucible.as_ref(), &[position.bump]];
+        let signer = &[&position_seeds[..]];
+
+        if debt_to_repay > 0 {
+            forge_crucibles::cpi::burn_ctoken(
+                CpiContext::new_with_signer(
+                    ctx.accounts.crucibles_program.to_account_info(),
+                    forge_crucibles::cpi::accounts::BurnCToken {
+                        user: ctx.accounts.position.to_account_info(),
+                        crucible: ctx.accounts.crucible.to_account_info(),
+                        base_mint: ctx.accounts.base_mint.to_account_info(),
+                        ctoken_mint: ctx.accounts.ctoken_mint.to_account_info(),
+                        user_ctoken_account: ctx.accounts.position_vault.to_account_info(),
+                        vault: ctx.accounts.crucible_vault.to_account_info(),
+                        user_token_account: ctx.accounts.position_base_account.to_account_info(),
+                        crucible_authority: ctx.accounts.crucible_authority.to_account_info(),
+                        token_program: ctx.accounts.token_program.to_account_info(),
+                        system_program: ctx.accounts.system_program.to_account_info(),
+                        forge_protocol: ctx.accounts.forge_protocol.as_ref().map(|p| p.to_account_info()),
+                        fee_vault: ctx.accounts.fee_vault.as_ref().map(|v| v.to_account_info()),
+                    },
+                    signer,
+                ),
+                shares_for_debt as u64,
+                debt_to_repay as u64,
+            )?;
+            ctx.accounts.position_base_account.reload()?;
+            require!(
+                ctx.accounts.position_base_account.amount as u128 >= debt_to_repay,
+                LvfError::PositionUnderwater
+            );
+
+            lending::cpi::repay(
+                CpiContext::new_with_signer(
+                    ctx.accounts.lending_program.to_account_info(),
+                    lending::cpi::accounts::Repay {
+                        market: ctx.accounts.lending_market.to_account_info(),
+                        user: ctx.accounts.position.to_account_info(),
+                        debt: ctx.accounts.debt.to_account_info(),
+                        user_base_account: ctx.accounts.position_base_account.to_account_info(),
+                        vault: ctx.accounts.lending_vault.to_account_info(),
+                        owner_receipt_account: None,
+                        collateral_vault: ctx.accounts.lending_collateral_vault.to_account_info(),
+                        token_program: ctx.accounts.token_program.to_account_info(),
+                    },
+                    signer,
+                ),
+                debt_to_repay as u64,
+            )?;
+            ctx.accounts.debt.reload()?;
+            ctx.accounts.position_base_account.reload()?;
+        }
+
+        // Return the unencumbered cTokens and any base left from rounding. A full close
+        // sweeps the vault's whole balance so stray transfers cannot block closing it.
+        let shares_returned = if full_close {
+            ctx.accounts.position_vault.reload()?;
+            ctx.accounts.position_vault.amount as u128
+        } else {
+            shares_to_close - shares_for_debt
+        };
+        if shares_returned > 0 {
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.position_vault.to_account_info(),
+                to: ctx.accounts.owner_ctoken_account.to_account_info(),
+                authority: ctx.accounts.position.to_account_info(),
+            };
+            token::transfer(CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer), shares_returned as u64)?;
+        }
+        let base_returned = ctx.accounts.position_base_account.amount;
+        if base_returned > 0 {
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.position_base_account.to_account_info(),
+                to: ctx.accounts.owner_base_account.to_account_info(),
+                authority: ctx.accounts.position.to_account_info(),
+            };
+            token::transfer(CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer), base_returned)?;
+        }
+
+        let remaining_debt = debt_value(&ctx.accounts.debt, &ctx.accounts.lending_market)?;
+        let position_key = ctx.accounts.position.key();
+        emit!(PositionClosed {
+            position: position_key,
+            owner,
+            close_bps,
+            debt_repaid: debt_to_repay as u64,
+            shares_returned: shares_returned as u64,
+            base_returned,
+        });
+
+        if full_close {
+            for account in [&ctx.accounts.position_vault, &ctx.accounts.position_base_account] {
+                token::close_account(CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    token::CloseAccount {
+                        account: account.to_account_info(),
+                        destination: ctx.accounts.owner.to_account_info(),
+                        authority: ctx.accounts.position.to_account_info(),
+                    },
+                    signer,
+                ))?;
+            }
+            return ctx.accounts.position.close(ctx.accounts.owner.to_account_info());
+        }
+
+        let position = &mut ctx.accounts.position;
+        position.position_shares -= shares_to_close;
+        position.borrowed_amount = remaining_debt;
+        Ok(())
     }
 
-    pub fn liquidate_position(_ctx: Context<LiquidatePosition>) -> Result<()> {
-        err!(LvfError::Unimplemented)
+    /// Repay part of an unhealthy position's debt and seize cTokens worth the repayment
+    /// plus the bounty. When the position's cTokens cannot cover that, the liquidator
+    /// repays only what they are worth and takes all of them; the shortfall is written off
+    /// in the lending market and the position is closed.
+    pub fn liquidate_position(ctx: Context<LiquidatePosition>, repay_amount: u64) -> Result<()> {
+        require!(repay_amount > 0, LvfError::InvalidAmount);
+
+        // Bring the lending index up to date before measuring debt
+        lending::cpi::accrue_interest(CpiContext::new(
+            ctx.accounts.lending_program.to_account_info(),
+            lending::cpi::accounts::AccrueInterest {
+                market: ctx.accounts.lending_market.to_account_info(),
+                vault: ctx.accounts.lending_vault.to_account_info(),
+            },
+        ))?;
+        ctx.accounts.lending_market.reload()?;
+
+        let cfg = &ctx.accounts.config;
+        let position = &ctx.accounts.position;
+        let fee_rate = ctx.accounts.crucible.fee_rate;
+        let rate = ctoken_exchange_rate(ctx.accounts.crucible_vault.amount, ctx.accounts.ctoken_mint.supply)?;
+        let collateral_value = shares_value(position.position_shares, rate)?;
+        let debt = debt_value(&ctx.accounts.debt, &ctx.accounts.lending_market)?;
+        let ltv_bps = ltv_bps(debt, collateral_value)?;
+        require!(ltv_bps >= cfg.liquidation_threshold_bps, LvfError::PositionHealthy);
+
+        // Partial liquidation: at most CLOSE_FACTOR_BPS of the debt per call, or all of it
+        // once the remainder would be dust
+        let dust = liquidation_dust(ctx.accounts.lending_market.decimals);
+        let mut repay = (repay_amount as u128).min(max_liquidation_repay(debt, dust)?) as u64;
+
+        // Seize cTokens that redeem, net of the crucible withdrawal fee, for the repaid
+        // value plus the bounty. A repayment the whole position cannot pay for is cut down
+        // to what it can, and seizes everything.
+        let capacity = liquidation_capacity(collateral_value, fee_rate, cfg.liquidation_bounty_bps)?;
+        let exhausted = repay as u128 >= capacity;
+        let seized = if exhausted {
+            repay = capacity as u64;
+            position.position_shares
+        } else {
+            let seize_value = bps_of(repay as u128, 10_000 + cfg.liquidation_bounty_bps, Rounding::Down)?;
+            rate.div_amount(gross_of_fee(seize_value, fee_rate, Rounding::Down)?, Rounding::Down)?
+                .min(position.position_shares)
+        };
+
+        // Liquidator repays the position's debt into the lending market
+        if repay > 0 {
+            lending::cpi::repay(
+                CpiContext::new(
+                    ctx.accounts.lending_program.to_account_info(),
+                    lending::cpi::accounts::Repay {
+                        market: ctx.accounts.lending_market.to_account_info(),
+                        user: ctx.accounts.liquidator.to_account_info(),
+                        debt: ctx.accounts.debt.to_account_info(),
+                        user_base_account: ctx.accounts.liquidator_base_account.to_account_info(),
+                        vault: ctx.accounts.lending_vault.to_account_info(),
+                        owner_receipt_account: None,
+                        collateral_vault: ctx.accounts.lending_collateral_vault.to_account_info(),
+                        token_program: ctx.accounts.token_program.to_account_info(),
+                    },
+                ),
+                repay,
+            )?;
+            ctx.accounts.debt.reload()?;
+        }
+
+        let owner = position.owner;
+        let crucible = position.crucible;
+        let bump = position.bump;
+        let seeds = &[b"lvf_position", owner.as_ref(), crucible.as_ref(), &[bump]];
+        let signer = &[&seeds[..]];
+
+        if seized > 0 {
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.position_vault.to_account_info(),
+                to: ctx.accounts.liquidator_ctoken_account.to_account_info(),
+                authority: ctx.accounts.position.to_account_info(),
+            };
+            token::transfer(CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer), seized as u64)?;
+        }
+
+        let remaining_debt = debt_value(&ctx.accounts.debt, &ctx.accounts.lending_market)?;
+        if exhausted {
+            return close_exhausted_position(ctx.accounts, repay, seized, remaining_debt, ltv_bps);
+        }
+
+        // Once the debt is cleared, the remainder goes back to the owner
+        let mut returned = 0u128;
+        let left = position.position_shares - seized;
+        if remaining_debt == 0 && left > 0 {
+            returned = left;
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.position_vault.to_account_info(),
+                to: ctx.accounts.owner_ctoken_account.to_account_info(),
+                authority: ctx.accounts.position.to_account_info(),
+            };
+            token::transfer(CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer), returned as u64)?;
+        }
+
+        let position = &mut ctx.accounts.position;
+        position.position_shares = left - returned;
+        position.borrowed_amount = remaining_debt;
+
+        emit!(PositionLiquidated {
+            position: position.key(),
+            owner,
+            liquidator: ctx.accounts.liquidator.key(),
+            repaid: repay,
+            shares_seized: seized as u64,
+            shares_returned: returned as u64,
+            shortfall: 0,
+            ltv_bps,
+        });
+        Ok(())
     }
 
-    pub fn health_check(_ctx: Context<HealthCheck>) -> Result<u64> {
-        // Return LTV in basis points (stub)
-        Ok(0)
+    pub fn health_check(ctx: Context<HealthCheck>) -> Result<HealthReport> {
+        lending::cpi::accrue_interest(CpiContext::new(
+            ctx.accounts.lending_program.to_account_info(),
+            lending::cpi::accounts::AccrueInterest {
+                market: ctx.accounts.lending_market.to_account_info(),
+                vault: ctx.accounts.lending_vault.to_account_info(),
+            },
+        ))?;
+        ctx.accounts.lending_market.reload()?;
+
+        let position = &ctx.accounts.position;
+        let rate = ctoken_exchange_rate(ctx.accounts.crucible_vault.amount, ctx.accounts.ctoken_mint.supply)?;
+        let collateral_value = shares_value(position.position_shares, rate)?;
+        let debt = debt_value(&ctx.accounts.debt, &ctx.accounts.lending_market)?;
+
+        let price = oracle::get_price_default(&ctx.accounts.price_feed)?;
+        let decimals = ctx.accounts.base_mint.decimals;
+
+        let report = HealthReport {
+            ltv_bps: ltv_bps(debt, collateral_value)?,
+            health_factor: health_factor(collateral_value, debt, ctx.accounts.config.liquidation_threshold_bps)?,
+            collateral_value: collateral_value.min(u64::MAX as u128) as u64,
+            debt: debt.min(u64::MAX as u128) as u64,
+            collateral_value_usd: oracle::usd_value(collateral_value, decimals, &price)?,
+            debt_usd: oracle::usd_value(debt, decimals, &price)?,
+        };
+
+        emit!(PositionHealth {
+            position: position.key(),
+            ltv_bps: report.ltv_bps,
+            health_factor: report.health_factor,
+            collateral_value: report.collateral_value,
+            debt: report.debt,
+            collateral_value_usd: report.collateral_value_usd,
+            debt_usd: report.debt_usd,
+        });
+        Ok(report)
     }
 }
 
@@ -77,28 +486,446 @@ pub struct Pause<'info> {
 
 #[derive(Accounts)]
 pub struct OpenPosition<'info> {
+    #[account(seeds = [b"lvf_config"], bump = config.bump)]
     pub config: Account<'info, LvfConfig>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        init,
+        payer = owner,
+        space = LvfPosition::SIZE,
+        seeds = [b"lvf_position", owner.key().as_ref(), crucible.key().as_ref()],
+        bump,
+    )]
+    pub position: Box<Account<'info, LvfPosition>>,
+    #[account(mut, has_one = base_mint, has_one = ctoken_mint)]
+    pub crucible: Box<Account<'info, Crucible>>,
+    pub base_mint: Box<Account<'info, Mint>>,
+    #[account(mut)]
+    pub ctoken_mint: Box<Account<'info, Mint>>,
+    #[account(mut, address = crucible.vault)]
+    pub crucible_vault: Box<Account<'info, TokenAccount>>,
+    /// CHECK: crucible mint/vault authority PDA, validated by the crucibles program
+    pub crucible_authority: UncheckedAccount<'info>,
+    #[account(mut, token::mint = ctoken_mint, token::authority = owner)]
+    pub owner_ctoken_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = owner,
+        associated_token::mint = ctoken_mint,
+        associated_token::authority = position,
+    )]
+    pub position_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = owner,
+        associated_token::mint = base_mint,
+        associated_token::authority = position,
+    )]
+    pub position_base_account: Box<Account<'info, TokenAccount>>,
+    #[account(mut, constraint = lending_market.base_mint == base_mint.key() @ LvfError::MarketMismatch)]
+    pub lending_market: Box<Account<'info, Market>>,
+    #[account(mut, address = lending_market.vault)]
+    pub lending_vault: Box<Account<'info, TokenAccount>>,
+    /// CHECK: position debt account, created by the lending program
+    #[account(mut)]
+    pub debt: UncheckedAccount<'info>,
+    pub lending_program: Program<'info, Lending>,
+    pub crucibles_program: Program<'info, ForgeCrucibles>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    /// CHECK: forge_core protocol account, validated by the crucibles program
+    pub forge_protocol: Option<UncheckedAccount<'info>>,
+    /// CHECK: crucible treasury fee vault, validated by the crucibles program
+    #[account(mut)]
+    pub fee_vault: Option<UncheckedAccount<'info>>,
 }
 
 #[derive(Accounts)]
 pub struct ClosePosition<'info> {
-    pub config: Account<'info, LvfConfig>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"lvf_position", owner.key().as_ref(), crucible.key().as_ref()],
+        bump = position.bump,
+        has_one = owner,
+        has_one = crucible,
+    )]
+    pub position: Box<Account<'info, LvfPosition>>,
+    #[account(mut, has_one = base_mint, has_one = ctoken_mint)]
+    pub crucible: Box<Account<'info, Crucible>>,
+    pub base_mint: Box<Account<'info, Mint>>,
+    #[account(mut, address = position.p_token_mint)]
+    pub ctoken_mint: Box<Account<'info, Mint>>,
+    #[account(mut, address = crucible.vault)]
+    pub crucible_vault: Box<Account<'info, TokenAccount>>,
+    /// CHECK: crucible mint/vault authority PDA, validated by the crucibles program
+    pub crucible_authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        associated_token::mint = ctoken_mint,
+        associated_token::authority = position,
+    )]
+    pub position_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        associated_token::mint = base_mint,
+        associated_token::authority = position,
+    )]
+    pub position_base_account: Box<Account<'info, TokenAccount>>,
+    #[account(mut, token::mint = ctoken_mint, token::authority = owner)]
+    pub owner_ctoken_account: Box<Account<'info, TokenAccount>>,
+    #[account(mut, token::mint = base_mint, token::authority = owner)]
+    pub owner_base_account: Box<Account<'info, TokenAccount>>,
+    #[account(mut, address = position.pair_market)]
+    pub lending_market: Box<Account<'info, Market>>,
+    #[account(mut, address = lending_market.vault)]
+    pub lending_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"debt", lending_market.key().as_ref(), position.key().as_ref()],
+        bump = debt.bump,
+        seeds::program = lending::ID,
+    )]
+    pub debt: Box<Account<'info, DebtAccount>>,
+    /// CHECK: lending market collateral vault, validated by the lending program
+    #[account(mut)]
+    pub lending_collateral_vault: UncheckedAccount<'info>,
+    pub lending_program: Program<'info, Lending>,
+    pub crucibles_program: Program<'info, ForgeCrucibles>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: forge_core protocol account, validated by the crucibles program
+    pub forge_protocol: Option<UncheckedAccount<'info>>,
+    /// CHECK: crucible treasury fee vault, validated by the crucibles program
+    #[account(mut)]
+    pub fee_vault: Option<UncheckedAccount<'info>>,
 }
 
 #[derive(Accounts)]
 pub struct LiquidatePosition<'info> {
+    #[account(seeds = [b"lvf_config"], bump = config.bump)]
     pub config: Account<'info, LvfConfig>,
+    #[account(
+        mut,
+        seeds = [b"lvf_position", position.owner.as_ref(), position.crucible.as_ref()],
+        bump = position.bump,
+    )]
+    pub position: Account<'info, LvfPosition>,
+    #[account(
+        mut,
+        associated_token::mint = ctoken_mint,
+        associated_token::authority = position,
+    )]
+    pub position_vault: Account<'info, TokenAccount>,
+    #[account(address = position.p_token_mint)]
+    pub ctoken_mint: Account<'info, Mint>,
+    /// Crucible whose withdrawal fee the seized cTokens are priced net of
+    #[account(address = position.crucible)]
+    pub crucible: Box<Account<'info, Crucible>>,
+    #[account(
+        seeds = [b"vault", position.crucible.as_ref()],
+        bump,
+        seeds::program = forge_crucibles::ID,
+    )]
+    pub crucible_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = ctoken_mint,
+        constraint = owner_ctoken_account.owner == position.owner @ LvfError::Unauthorized,
+    )]
+    pub owner_ctoken_account: Account<'info, TokenAccount>,
+    /// CHECK: position owner, receives the rent of a position closed by liquidation
+    #[account(mut, address = position.owner)]
+    pub owner: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        associated_token::mint = crucible.base_mint,
+        associated_token::authority = position,
+    )]
+    pub position_base_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = crucible.base_mint,
+        constraint = owner_base_account.owner == position.owner @ LvfError::Unauthorized,
+    )]
+    pub owner_base_account: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub liquidator: Signer<'info>,
+    #[account(mut)]
+    pub liquidator_base_account: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = ctoken_mint)]
+    pub liquidator_ctoken_account: Account<'info, TokenAccount>,
+    #[account(mut, address = position.pair_market)]
+    pub lending_market: Account<'info, Market>,
+    #[account(mut, address = lending_market.vault)]
+    pub lending_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"debt", lending_market.key().as_ref(), position.key().as_ref()],
+        bump = debt.bump,
+        seeds::program = lending::ID,
+    )]
+    pub debt: Account<'info, DebtAccount>,
+    /// CHECK: lending market collateral vault, validated by the lending program
+    #[account(mut)]
+    pub lending_collateral_vault: UncheckedAccount<'info>,
+    pub lending_program: Program<'info, Lending>,
+    pub token_program: Program<'info, Token>,
 }
 
 #[derive(Accounts)]
 pub struct HealthCheck<'info> {
+    #[account(seeds = [b"lvf_config"], bump = config.bump)]
     pub config: Account<'info, LvfConfig>,
+    #[account(
+        seeds = [b"lvf_position", position.owner.as_ref(), position.crucible.as_ref()],
+        bump = position.bump,
+    )]
+    pub position: Account<'info, LvfPosition>,
+    /// CHECK: crucible the position's cTokens belong to
+    #[account(address = position.crucible)]
+    pub crucible: UncheckedAccount<'info>,
+    /// cToken to base rate source: the crucible vault and cToken supply
+    #[account(
+        seeds = [b"vault", crucible.key().as_ref()],
+        bump,
+        seeds::program = forge_crucibles::ID,
+    )]
+    pub crucible_vault: Account<'info, TokenAccount>,
+    #[account(address = position.p_token_mint)]
+    pub ctoken_mint: Account<'info, Mint>,
+    #[account(mut, address = position.pair_market)]
+    pub lending_market: Account<'info, Market>,
+    #[account(address = lending_market.vault)]
+    pub lending_vault: Account<'info, TokenAccount>,
+    #[account(address = lending_market.base_mint)]
+    pub base_mint: Account<'info, Mint>,
+    /// Base to USD price source registered on the lending market
+    #[account(address = lending_market.price_feed)]
+    pub price_feed: Account<'info, PriceFeed>,
+    #[account(
+        seeds = [b"debt", lending_market.key().as_ref(), position.key().as_ref()],
+        bump = debt.bump,
+        seeds::program = lending::ID,
+    )]
+    pub debt: Account<'info, DebtAccount>,
+    pub lending_program: Program<'info, Lending>,
+}
+
+/// Finish a liquidation that seized every cToken: write off the debt they could not
+/// cover, sweep stray balances to the owner and close the position and its token accounts.
+fn close_exhausted_position(
+    accounts: &mut LiquidatePosition,
+    repaid: u64,
+    seized: u128,
+    shortfall: u128,
+    ltv_bps: u64,
+) -> Result<()> {
+    let owner = accounts.position.owner;
+    let crucible = accounts.position.crucible;
+    let position_seeds = &[b"lvf_position", owner.as_ref(), crucible.as_ref(), &[accounts.position.bump]];
+    let config_seeds = &[b"lvf_config".as_ref(), &[accounts.config.bump]];
+    let signer = &[&position_seeds[..]];
+
+    if shortfall > 0 {
+        lending::cpi::write_off_delegated(
+            CpiContext::new_with_signer(
+                accounts.lending_program.to_account_info(),
+                lending::cpi::accounts::WriteOffDelegated {
+                    market: accounts.lending_market.to_account_info(),
+                    delegate: accounts.config.to_account_info(),
+                    user: accounts.position.to_account_info(),
+                    debt: accounts.debt.to_account_info(),
+                    vault: accounts.lending_vault.to_account_info(),
+                },
+                &[&config_seeds[..], &position_seeds[..]],
+            ),
+            u64::try_from(shortfall).map_err(|_| LvfError::MathOverflow)?,
+        )?;
+    }
+
+    // Token accounts must be empty to close, so anything sent to them goes to the owner
+    accounts.position_vault.reload()?;
+    let shares_returned = accounts.position_vault.amount;
+    for (from, to, amount) in [
+        (&accounts.position_vault, accounts.owner_ctoken_account.to_account_info(), shares_returned),
+        (&accounts.position_base_account, accounts.owner_base_account.to_account_info(), accounts.position_base_account.amount),
+    ] {
+        if amount > 0 {
+            let cpi_accounts = Transfer {
+                from: from.to_account_info(),
+                to,
+                authority: accounts.position.to_account_info(),
+            };
+            token::transfer(CpiContext::new_with_signer(accounts.token_program.to_account_info(), cpi_accounts, signer), amount)?;
+        }
+    }
+    for account in [&accounts.position_vault, &accounts.position_base_account] {
+        token::close_account(CpiContext::new_with_signer(
+            accounts.token_program.to_account_info(),
+            token::CloseAccount {
+                account: account.to_account_info(),
+                destination: accounts.owner.to_account_info(),
+                authority: accounts.position.to_account_info(),
+            },
+            signer,
+        ))?;
+    }
+
+    emit!(PositionLiquidated {
+        position: accounts.position.key(),
+        owner,
+        liquidator: accounts.liquidator.key(),
+        repaid,
+        shares_seized: seized as u64,
+        shares_returned,
+        shortfall: u64::try_from(shortfall).map_err(|_| LvfError::MathOverflow)?,
+        ltv_bps,
+    });
+    accounts.position.close(accounts.owner.to_account_info())
+}
+
+/// cToken exchange rate (base per cToken) from the crucible vault, including the
+/// crucible's virtual shares/assets.
+pub fn ctoken_exchange_rate(vault_amount: u64, ctoken_supply: u64) -> Result<Decimal> {
+    Ok(Decimal::from_ratio(
+        vault_amount as u128 + VIRTUAL_ASSETS as u128,
+        ctoken_supply as u128 + VIRTUAL_SHARES as u128,
+        Rounding::Down,
+    )?)
+}
+
+/// Base-token value of a cToken share balance at the given exchange rate.
+pub fn shares_value(shares: u128, rate: Decimal) -> Result<u128> {
+    Ok(rate.mul_amount(shares, Rounding::Down)?)
+}
+
+/// Base that must be redeemed from the crucible so that `net` is left after its
+/// `fee_rate` withdrawal fee.
+pub fn gross_of_fee(net: u128, fee_rate: u64, rounding: Rounding) -> Result<u128> {
+    Ok(mul_div(
+        net,
+        FEE_RATE_SCALE as u128,
+        (FEE_RATE_SCALE - fee_rate) as u128,
+        rounding,
+    )?)
+}
+
+/// Most debt a liquidation can repay against `collateral_value` of cTokens: what they
+/// redeem for after the crucible withdrawal fee, less the liquidation bounty.
+pub fn liquidation_capacity(collateral_value: u128, fee_rate: u64, bounty_bps: u64) -> Result<u128> {
+    let net = mul_div(
+        collateral_value,
+        (FEE_RATE_SCALE - fee_rate) as u128,
+        FEE_RATE_SCALE as u128,
+        Rounding::Down,
+    )?;
+    Ok(mul_div(net, BPS_SCALE, BPS_SCALE + bounty_bps as u128, Rounding::Down)?)
+}
+
+/// Debt currently owed on a lending debt account, rounded up.
+pub fn debt_value(debt: &DebtAccount, market: &Market) -> Result<u128> {
+    lending::debt_value(debt.scaled_debt, market.borrow_index)
+}
+
+/// Dust threshold in base units for a mint with `decimals`: 1/LIQUIDATION_DUST_DIVISOR
+/// of a whole token, and at least one unit.
+pub fn liquidation_dust(decimals: u8) -> u128 {
+    let one_token = 10u128.checked_pow(decimals as u32).unwrap_or(u128::MAX);
+    (one_token / LIQUIDATION_DUST_DIVISOR).max(1)
+}
+
+/// Most debt one liquidation may repay: the close factor, or the whole debt when what
+/// the close factor leaves behind is at or below `dust`.
+pub fn max_liquidation_repay(debt: u128, dust: u128) -> Result<u128> {
+    let max_repay = bps_of(debt, CLOSE_FACTOR_BPS, Rounding::Up)?;
+    if debt - max_repay <= dust {
+        return Ok(debt);
+    }
+    Ok(max_repay)
+}
+
+/// Loan-to-value in basis points; a position with debt and no collateral is u64::MAX.
+pub fn ltv_bps(debt: u128, collateral_value: u128) -> Result<u64> {
+    if debt == 0 {
+        return Ok(0);
+    }
+    if collateral_value == 0 {
+        return Ok(u64::MAX);
+    }
+    let ltv = mul_div(debt, BPS_SCALE, collateral_value, Rounding::Down)?;
+    Ok(ltv.min(u64::MAX as u128) as u64)
+}
+
+/// Health factor as a scaled `Decimal`: 1.0 means the position sits exactly at the
+/// liquidation threshold. A position without debt reports u128::MAX.
+pub fn health_factor(collateral_value: u128, debt: u128, liquidation_threshold_bps: u64) -> Result<u128> {
+    if debt == 0 {
+        return Ok(u128::MAX);
+    }
+    let threshold_value = bps_of(collateral_value, liquidation_threshold_bps, Rounding::Down)?;
+    Ok(Decimal::from_ratio(threshold_value, debt, Rounding::Down)?.to_scaled())
+}
+
+#[event]
+pub struct PositionOpened {
+    pub position: Pubkey,
+    pub owner: Pubkey,
+    pub crucible: Pubkey,
+    pub collateral_amount: u64,
+    pub borrowed_amount: u64,
+    pub position_shares: u64,
+    pub leverage_bps: u64,
+}
+
+#[event]
+pub struct PositionClosed {
+    pub position: Pubkey,
+    pub owner: Pubkey,
+    pub close_bps: u64,
+    pub debt_repaid: u64,
+    pub shares_returned: u64,
+    pub base_returned: u64,
+}
+
+#[event]
+pub struct PositionHealth {
+    pub position: Pubkey,
+    pub ltv_bps: u64,
+    pub health_factor: u128,
+    pub collateral_value: u64,
+    pub debt: u64,
+    pub collateral_value_usd: u64,
+    pub debt_usd: u64,
+}
+
+#[event]
+pub struct PositionLiquidated {
+    pub position: Pubkey,
+    pub owner: Pubkey,
+    pub liquidator: Pubkey,
+    pub repaid: u64,
+    pub shares_seized: u64,
+    pub shares_returned: u64,
+    pub shortfall: u64, // debt the seized cTokens could not cover, written off
+    pub ltv_bps: u64,
 }
 
 #[error_code]
 pub enum LvfError {
     #[msg("Unauthorized")] Unauthorized,
     #[msg("Unimplemented")] Unimplemented,
+    #[msg("Invalid amount")] InvalidAmount,
+    #[msg("Position is above the liquidation threshold")] PositionHealthy,
+    #[msg("Math overflow")] MathOverflow,
+    #[msg("LVF is paused")] Paused,
+    #[msg("Invalid leverage")] InvalidLeverage,
+    #[msg("Lending market does not match the crucible")] MarketMismatch,
+    #[msg("Position collateral does not cover its debt")] PositionUnderwater,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -108,9 +935,105 @@ pub struct InitializeLvfParams {
     pub liquidation_bounty_bps: u64,
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct HealthReport {
+    pub ltv_bps: u64,
+    pub health_factor: u128,
+    pub collateral_value: u64,
+    pub debt: u64,
+    pub collateral_value_usd: u64, // scaled by 1e6
+    pub debt_usd: u64,             // scaled by 1e6
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct OpenPositionParams {
+    pub collateral_amount: u64, // cTokens locked by the owner
     pub leverage_bps: u64,
+    pub min_ctokens_out: u64,   // slippage bound on the cTokens minted from the loan
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use forge_crucibles::state::MAX_FEE_RATE;
+    use forge_math::SCALE;
+
+    #[test]
+    fn liquidation_dust_scales_with_decimals() {
+        assert_eq!(liquidation_dust(0), 1);
+        assert_eq!(liquidation_dust(2), 1);
+        assert_eq!(liquidation_dust(6), 1_000);
+        assert_eq!(liquidation_dust(9), 1_000_000);
+        assert_eq!(liquidation_dust(u8::MAX), u128::MAX / LIQUIDATION_DUST_DIVISOR);
+    }
+
+    #[test]
+    fn max_liquidation_repay_applies_close_factor() {
+        let dust = liquidation_dust(6);
+        assert_eq!(max_liquidation_repay(1_000_000, dust).unwrap(), 500_000);
+        // the close factor rounds up so odd debts shrink by at least half
+        assert_eq!(max_liquidation_repay(1_000_001, dust).unwrap(), 500_001);
+    }
+
+    #[test]
+    fn max_liquidation_repay_sweeps_dust() {
+        let dust = liquidation_dust(6);
+        // what the close factor leaves behind is exactly dust: repay everything
+        assert_eq!(max_liquidation_repay(2_000, dust).unwrap(), 2_000);
+        assert_eq!(max_liquidation_repay(1, dust).unwrap(), 1);
+        // one unit above the dust remainder keeps the close factor
+        assert_eq!(max_liquidation_repay(2_002, dust).unwrap(), 1_001);
+        // a zero-decimal mint sweeps only a single-unit remainder
+        assert_eq!(max_liquidation_repay(3, liquidation_dust(0)).unwrap(), 3);
+        assert_eq!(max_liquidation_repay(5, liquidation_dust(0)).unwrap(), 3);
+    }
+
+    #[test]
+    fn max_liquidation_repay_zero_debt() {
+        assert_eq!(max_liquidation_repay(0, liquidation_dust(6)).unwrap(), 0);
+    }
+
+    #[test]
+    fn gross_of_fee_covers_the_withdrawal_fee() {
+        assert_eq!(gross_of_fee(1_000, 0, Rounding::Down).unwrap(), 1_000);
+        // 0.2% fee: 1_002 redeemed leaves 1_000 after the fee
+        assert_eq!(gross_of_fee(1_000, 200, Rounding::Down).unwrap(), 1_002);
+        assert_eq!(gross_of_fee(1_000, 200, Rounding::Up).unwrap(), 1_003);
+        // 10% fee
+        assert_eq!(gross_of_fee(900, MAX_FEE_RATE, Rounding::Down).unwrap(), 1_000);
+    }
+
+    #[test]
+    fn liquidation_capacity_nets_fee_and_bounty() {
+        assert_eq!(liquidation_capacity(10_500, 0, 500).unwrap(), 10_000);
+        assert_eq!(liquidation_capacity(10_000, 0, 0).unwrap(), 10_000);
+        // 0.2% fee leaves 9_980 to split between repayment and bounty
+        assert_eq!(liquidation_capacity(10_000, 200, 500).unwrap(), 9_504);
+        assert_eq!(liquidation_capacity(0, 200, 500).unwrap(), 0);
+    }
 
+    #[test]
+    fn ltv_bps_edges() {
+        assert_eq!(ltv_bps(0, 0).unwrap(), 0);
+        assert_eq!(ltv_bps(0, 1_000).unwrap(), 0);
+        assert_eq!(ltv_bps(1, 0).unwrap(), u64::MAX);
+        assert_eq!(ltv_bps(500, 1_000).unwrap(), 5_000);
+        assert_eq!(ltv_bps(1_500, 1_000).unwrap(), 15_000);
+        // rounds down in the borrower's favor
+        assert_eq!(ltv_bps(1, 3).unwrap(), 3_333);
+        assert_eq!(ltv_bps(u64::MAX as u128 * 10_000, 1).unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn health_factor_edges() {
+        assert_eq!(health_factor(1_000, 0, 8_000).unwrap(), u128::MAX);
+        // exactly at the liquidation threshold
+        assert_eq!(health_factor(1_000, 800, 8_000).unwrap(), SCALE);
+        assert_eq!(health_factor(1_000, 400, 8_000).unwrap(), 2 * SCALE);
+        assert_eq!(health_factor(1_000, 1_600, 8_000).unwrap(), SCALE / 2);
+        assert_eq!(health_factor(0, 1, 8_000).unwrap(), 0);
+        // rounds down so a position is never reported healthier than it is
+        assert_eq!(health_factor(1_000, 3, 1).unwrap(), 0);
+        assert_eq!(health_factor(3_000, 900, 10_000).unwrap(), 3_333_333_333);
+    }
+}
@@ -23,7 +23,7 @@ pub struct LvfPosition {
     pub lp_pool: Pubkey,
     pub position_shares: u128,
     pub borrowed_amount: u128,
-    pub entry_exchange_rate: u128,
+    pub entry_exchange_rate: u128, // forge_math::SCALE fixed point
     pub leverage_bps: u64,
     pub bump: u8,
 }
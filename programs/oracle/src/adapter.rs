@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+
+use crate::state::PriceFeed;
+use crate::OracleError;
+
+/// Default maximum age of a price before it is rejected
+pub const MAX_PRICE_AGE_SECS: i64 = 60;
+/// Default maximum confidence interval, as bps of the price (2%)
+pub const MAX_CONF_BPS: u64 = 200;
+
+/// A validated oracle price.
+#[derive(Clone, Copy, Debug)]
+pub struct OraclePrice {
+    pub price: u64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+}
+
+impl OraclePrice {
+    /// Price rescaled to `decimals` fixed-point digits (e.g. 6 for USDC-style prices).
+    pub fn scaled(&self, decimals: u32) -> Result<u64> {
+        let shift = decimals as i32 + self.expo;
+        let value = if shift >= 0 {
+            (self.price as u128).checked_mul(10u128.pow(shift as u32))
+        } else {
+            Some(self.price as u128 / 10u128.pow((-shift) as u32))
+        }
+        .ok_or(OracleError::MathOverflow)?;
+        Ok(u64::try_from(value).map_err(|_| OracleError::MathOverflow)?)
+    }
+}
+
+/// Read a feed, rejecting non-positive, stale or low-confidence prices.
+pub fn get_price(feed: &PriceFeed, now: i64, max_age_secs: i64, max_conf_bps: u64) -> Result<OraclePrice> {
+    require!(feed.price > 0, OracleError::InvalidPrice);
+    require!(
+        now.saturating_sub(feed.publish_time) <= max_age_secs,
+        OracleError::StalePrice
+    );
+
+    let price = feed.price as u64;
+    let conf_bps = (feed.conf as u128) * 10_000 / price as u128;
+    require!(conf_bps <= max_conf_bps as u128, OracleError::PriceTooUncertain);
+
+    Ok(OraclePrice { price, conf: feed.conf, expo: feed.expo, publish_time: feed.publish_time })
+}
+
+/// Read a feed with the default staleness and confidence limits.
+pub fn get_price_default(feed: &PriceFeed) -> Result<OraclePrice> {
+    get_price(feed, Clock::get()?.unix_timestamp, MAX_PRICE_AGE_SECS, MAX_CONF_BPS)
+}
+
+/// Decimals of USD values produced by `usd_value`
+pub const USD_DECIMALS: u32 = 6;
+
+/// USD value (scaled by 10^USD_DECIMALS) of `amount` base units of a mint with `mint_decimals`.
+pub fn usd_value(amount: u128, mint_decimals: u8, price: &OraclePrice) -> Result<u64> {
+    let value = amount
+        .checked_mul(price.scaled(USD_DECIMALS)? as u128)
+        .ok_or(OracleError::MathOverflow)?
+        / 10u128.pow(mint_decimals as u32);
+    Ok(u64::try_from(value).map_err(|_| OracleError::MathOverflow)?)
+}
@@ -0,0 +1,95 @@
+// Summary: Admin-controlled mock oracle for localnet and testnet. Publishes Pyth-style
+// price feeds (price, confidence, exponent, publish time) per asset mint. Programs read
+// feeds through the `adapter` module, which enforces staleness and confidence limits.
+// Feeds are created by the forge_core protocol authority, which becomes their publisher.
+
+use anchor_lang::prelude::*;
+use forge_core::ForgeProtocol;
+
+pub mod adapter;
+pub mod state;
+pub use adapter::*;
+use state::*;
+
+declare_id!("Dpu9DFJgwj9qSRyKWZ8fAm2QpmF7ytGqwFYLpzKAEXSS");
+
+#[program]
+pub mod oracle {
+    use super::*;
+
+    pub fn initialize_feed(ctx: Context<InitializeFeed>, expo: i32) -> Result<()> {
+        require!((-18..=0).contains(&expo), OracleError::InvalidParams);
+
+        let feed = &mut ctx.accounts.feed;
+        feed.authority = ctx.accounts.authority.key();
+        feed.mint = ctx.accounts.mint.key();
+        feed.price = 0;
+        feed.conf = 0;
+        feed.expo = expo;
+        feed.publish_time = 0;
+        feed.bump = ctx.bumps.feed;
+        Ok(())
+    }
+
+    pub fn set_price(ctx: Context<SetPrice>, price: i64, conf: u64) -> Result<()> {
+        require!(price > 0, OracleError::InvalidParams);
+
+        let feed = &mut ctx.accounts.feed;
+        feed.price = price;
+        feed.conf = conf;
+        feed.publish_time = Clock::get()?.unix_timestamp;
+
+        emit!(PriceUpdated { mint: feed.mint, price, conf, expo: feed.expo, publish_time: feed.publish_time });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeFeed<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = PriceFeed::SIZE,
+        seeds = [b"price_feed", mint.key().as_ref()],
+        bump
+    )]
+    pub feed: Account<'info, PriceFeed>,
+    /// CHECK: asset the feed prices; only its key is recorded
+    pub mint: UncheckedAccount<'info>,
+    #[account(
+        seeds = [b"forge_protocol"],
+        bump = forge_protocol.bump,
+        seeds::program = forge_core::ID,
+        has_one = authority @ OracleError::Unauthorized,
+    )]
+    pub forge_protocol: Account<'info, ForgeProtocol>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetPrice<'info> {
+    #[account(mut, has_one = authority @ OracleError::Unauthorized)]
+    pub feed: Account<'info, PriceFeed>,
+    pub authority: Signer<'info>,
+}
+
+#[event]
+pub struct PriceUpdated {
+    pub mint: Pubkey,
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+}
+
+#[error_code]
+pub enum OracleError {
+    #[msg("Invalid parameters")] InvalidParams,
+    #[msg("Unauthorized")] Unauthorized,
+    #[msg("Price feed is stale")] StalePrice,
+    #[msg("Price confidence interval too wide")] PriceTooUncertain,
+    #[msg("Price is not positive")] InvalidPrice,
+    #[msg("Math overflow")] MathOverflow,
+}
@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+
+/// Pyth-style price feed: `price * 10^expo` is the asset price in USD.
+#[account]
+pub struct PriceFeed {
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub price: i64,
+    pub conf: u64, // confidence interval, same exponent as price
+    pub expo: i32,
+    pub publish_time: i64, // unix timestamp
+    pub bump: u8,
+}
+
+impl PriceFeed {
+    pub const SIZE: usize = 8 + // discriminator
+        32 + // authority
+        32 + // mint
+        8 +  // price
+        8 +  // conf
+        4 +  // expo
+        8 +  // publish_time
+        1;   // bump
+}